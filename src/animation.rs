@@ -0,0 +1,369 @@
+//! Time-based interpolation of values for animated visuals.
+//!
+//! Animations are computed from the current time, so visuals read their values
+//! when they draw and report [`crate::visual::Draw::is_animating`] while any animation is running.
+//! The app redraws such visuals on every frame until all their animations finish.
+
+use std::time::{Duration, Instant};
+
+use crate::{style::Color, Position, Size};
+
+/// Maps linear progress of an animation from 0 to 1 to the eased progress.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Starts slowly and accelerates.
+    EaseIn,
+    /// Starts quickly and decelerates.
+    EaseOut,
+    /// Accelerates in the first half and decelerates in the second one.
+    EaseInOut,
+    Custom(fn(f64) -> f64),
+}
+
+impl Easing {
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::Custom(easing) => easing(t),
+        }
+    }
+}
+
+/// Values which can be interpolated.
+pub trait Lerp {
+    /// Returns value between `self` at 0 and `to` at 1.
+    fn lerp(&self, to: &Self, t: f64) -> Self;
+}
+
+macro_rules! impl_lerp_for_numbers {
+    ($($number:ty),*) => {
+        $(
+            impl Lerp for $number {
+                fn lerp(&self, to: &Self, t: f64) -> Self {
+                    (*self as f64 + (*to as f64 - *self as f64) * t).round() as $number
+                }
+            }
+        )*
+    };
+}
+
+impl_lerp_for_numbers!(u8, u16, u32, i16, i32, usize);
+
+impl Lerp for f64 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        self + (to - self) * t as f32
+    }
+}
+
+impl Lerp for Position {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        Position::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        Size::new(
+            self.width.lerp(&to.width, t),
+            self.height.lerp(&to.height, t),
+        )
+    }
+}
+
+/// Colors are interpolated in RGB, named and ANSI colors are converted using the xterm palette.
+/// The reset color cannot be interpolated, so it is switched in the middle.
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f64) -> Self {
+        match (rgb(*self), rgb(*to)) {
+            (Some(from), Some(to)) => Color::Rgb {
+                r: from.0.lerp(&to.0, t),
+                g: from.1.lerp(&to.1, t),
+                b: from.2.lerp(&to.2, t),
+            },
+            _ if t < 0.5 => *self,
+            _ => *to,
+        }
+    }
+}
+
+/// Standard colors of the xterm palette.
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb { r, g, b } => return Some((r, g, b)),
+        Color::AnsiValue(index) => index,
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White => 15,
+    };
+
+    let rgb = match index {
+        0..=15 => ANSI_COLORS[index as usize],
+        // 6×6×6 color cube
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        // grayscale ramp
+        _ => {
+            let value = 8 + (index - 232) * 10;
+
+            (value, value, value)
+        }
+    };
+
+    Some(rgb)
+}
+
+/// Value which changes over time.
+pub trait Animation {
+    type Value;
+
+    fn value_at(&self, time: Instant) -> Self::Value;
+
+    fn is_finished_at(&self, time: Instant) -> bool;
+
+    /// Returns the current value.
+    fn value(&self) -> Self::Value {
+        self.value_at(Instant::now())
+    }
+
+    fn is_running(&self) -> bool {
+        !self.is_finished_at(Instant::now())
+    }
+}
+
+/// Animation from one value to another.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use termrs::animation::{Animation, Easing, Tween};
+///
+/// let tween = Tween::new(0.0, 10.0, Duration::from_secs(1)).with_easing(Easing::EaseOut);
+///
+/// assert_eq!(tween.value_at(Instant::now() + Duration::from_secs(2)), 10.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T> Tween<T>
+where
+    T: Lerp + Clone,
+{
+    /// Creates tween which starts now.
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Postpones the start of the tween, the initial value is kept until then.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.start += delay;
+        self
+    }
+
+    pub fn from(&self) -> &T {
+        &self.from
+    }
+
+    pub fn to(&self) -> &T {
+        &self.to
+    }
+
+    /// Starts the tween again from the current value to the new one
+    /// with the same duration and easing, e.g. when a scroll target changes.
+    pub fn retarget(&mut self, to: T) {
+        let now = Instant::now();
+
+        self.from = self.value_at(now);
+        self.to = to;
+        self.start = now;
+    }
+
+    /// Returns linear progress from 0 to 1 at the given time.
+    fn progress_at(&self, time: Instant) -> f64 {
+        if self.duration.is_zero() {
+            return if time >= self.start { 1.0 } else { 0.0 };
+        }
+
+        time.saturating_duration_since(self.start).as_secs_f64() / self.duration.as_secs_f64()
+    }
+}
+
+impl<T> Animation for Tween<T>
+where
+    T: Lerp + Clone,
+{
+    type Value = T;
+
+    fn value_at(&self, time: Instant) -> T {
+        let progress = self.progress_at(time);
+
+        if progress >= 1.0 {
+            self.to.clone()
+        } else {
+            self.from.lerp(&self.to, self.easing.apply(progress))
+        }
+    }
+
+    fn is_finished_at(&self, time: Instant) -> bool {
+        self.progress_at(time) >= 1.0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Keyframe<T> {
+    value: T,
+    /// Time of moving from the previous keyframe.
+    duration: Duration,
+    easing: Easing,
+}
+
+/// Animation through a sequence of values, each reached in its own time and easing.
+#[derive(Debug, Clone)]
+pub struct Keyframes<T> {
+    initial: T,
+    keyframes: Vec<Keyframe<T>>,
+    start: Instant,
+    repeat: bool,
+}
+
+impl<T> Keyframes<T>
+where
+    T: Lerp + Clone,
+{
+    /// Creates animation which starts now from the initial value.
+    pub fn new(initial: T) -> Self {
+        Self {
+            initial,
+            keyframes: vec![],
+            start: Instant::now(),
+            repeat: false,
+        }
+    }
+
+    /// Adds keyframe which is reached from the previous one in the given time.
+    pub fn then(mut self, value: T, duration: Duration, easing: Easing) -> Self {
+        self.keyframes.push(Keyframe {
+            value,
+            duration,
+            easing,
+        });
+        self
+    }
+
+    /// Sets whether the animation starts again from the initial value after the last keyframe.
+    pub fn with_repeat(mut self, repeat: bool) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.keyframes
+            .iter()
+            .map(|keyframe| keyframe.duration)
+            .sum()
+    }
+}
+
+impl<T> Animation for Keyframes<T>
+where
+    T: Lerp + Clone,
+{
+    type Value = T;
+
+    fn value_at(&self, time: Instant) -> T {
+        let total = self.duration();
+        let mut elapsed = time.saturating_duration_since(self.start);
+
+        if self.repeat && !total.is_zero() {
+            elapsed = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+        }
+
+        let mut from = &self.initial;
+
+        for keyframe in self.keyframes.iter() {
+            if elapsed < keyframe.duration {
+                let progress = elapsed.as_secs_f64() / keyframe.duration.as_secs_f64();
+
+                return from.lerp(&keyframe.value, keyframe.easing.apply(progress));
+            }
+
+            elapsed -= keyframe.duration;
+            from = &keyframe.value;
+        }
+
+        from.clone()
+    }
+
+    fn is_finished_at(&self, time: Instant) -> bool {
+        !self.repeat && time.saturating_duration_since(self.start) >= self.duration()
+    }
+}
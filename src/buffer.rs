@@ -1,6 +1,8 @@
+mod cell_grid;
 mod memory_buffer;
 mod terminal;
 
+pub(crate) use cell_grid::CellGrid;
 pub use {memory_buffer::VirtualBuffer, terminal::Terminal};
 
 use std::{error::Error, ops::Index};
@@ -87,3 +89,76 @@ pub trait WriteBuffer {
     /// Clears the given region of buffer
     fn clear_region(&mut self, region: Rect) -> Result<(), Box<dyn Error>>;
 }
+
+/// Writes the given styled symbols in a single row starting at the given position,
+/// grouping neighbour symbols with equal style into one write.
+pub(crate) fn write_cells(
+    buffer: &mut dyn WriteBuffer,
+    position: Position,
+    cells: &[(char, Style)],
+) -> Result<(), Box<dyn Error>> {
+    let mut start = 0;
+    let mut run = String::new();
+
+    while start < cells.len() {
+        let style = cells[start].1;
+        let mut end = start;
+
+        run.clear();
+
+        while end < cells.len() && cells[end].1 == style {
+            run.push(cells[end].0);
+            end += 1;
+        }
+
+        buffer.write_symbols(
+            Position::new(position.x + start as u16, position.y),
+            &run,
+            style,
+        )?;
+
+        start = end;
+    }
+
+    Ok(())
+}
+
+/// Clears cells of the regions which are not covered by any of the given rects.
+pub(crate) fn clear_uncovered(
+    buffer: &mut dyn WriteBuffer,
+    regions: &[Rect],
+    covered: &[Rect],
+) -> Result<(), Box<dyn Error>> {
+    let is_covered = |x, y| {
+        covered
+            .iter()
+            .any(|rect| rect.contains(Position::new(x, y)))
+    };
+
+    for region in regions {
+        for y in region.top()..region.bottom() {
+            let mut x = region.left();
+
+            while x < region.right() {
+                if is_covered(x, y) {
+                    x += 1;
+                    continue;
+                }
+
+                let end = (x..region.right())
+                    .find(|&x| is_covered(x, y))
+                    .unwrap_or(region.right());
+
+                buffer.write_symbols(
+                    Position::new(x, y),
+                    &" ".repeat((end - x) as usize),
+                    Style::default(),
+                )?;
+
+                x = end;
+            }
+        }
+    }
+
+    Ok(())
+}
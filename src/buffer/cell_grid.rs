@@ -0,0 +1,59 @@
+use std::error::Error;
+
+use crate::{
+    buffer::{write_cells, WriteBuffer},
+    style::Style,
+    Position, Size,
+};
+
+/// Two-dimensional array of styled symbols which is composed in memory
+/// and then written to a [`WriteBuffer`] row by row.
+pub(crate) struct CellGrid {
+    size: Size,
+    cells: Vec<(char, Style)>,
+}
+
+impl CellGrid {
+    pub fn new(size: Size, style: Style) -> Self {
+        Self {
+            size,
+            cells: vec![(' ', style); size.width as usize * size.height as usize],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Sets symbol and style of the cell, positions outside the grid are ignored.
+    pub fn set(&mut self, x: u16, y: u16, symbol: char, style: Style) {
+        if x < self.size.width && y < self.size.height {
+            self.cells[y as usize * self.size.width as usize + x as usize] = (symbol, style);
+        }
+    }
+
+    /// Writes the text starting from the given cell, clipping it by the grid width.
+    pub fn set_text(&mut self, x: u16, y: u16, text: &str, style: Style) {
+        for (index, symbol) in text.chars().enumerate() {
+            let x = x as usize + index;
+
+            if x >= self.size.width as usize {
+                break;
+            }
+
+            self.set(x as u16, y, symbol, style);
+        }
+    }
+
+    pub fn write_to(&self, buffer: &mut dyn WriteBuffer) -> Result<(), Box<dyn Error>> {
+        if self.size.width == 0 {
+            return Ok(());
+        }
+
+        for (y, row) in self.cells.chunks(self.size.width as usize).enumerate() {
+            write_cells(buffer, Position::new(0, y as u16), row)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     buffer::{ReadBuffer, WriteBuffer},
     style::{Color, Style},
-    Attribute, Position, Rect,
+    Attribute, Position, Rect, Size,
 };
 
 pub struct VirtualBuffer<'a> {
@@ -115,7 +115,7 @@ impl<'a> WriteBuffer for VirtualBuffer<'a> {
     }
 
     fn clear(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.clear_region(self.region)
+        self.clear_region(Size::from(self.region).into())
     }
 
     fn clear_region(&mut self, rect: Rect) -> Result<(), Box<dyn std::error::Error>> {
@@ -139,7 +139,7 @@ impl<'a> WriteBuffer for VirtualBuffer<'a> {
         symbols: &str,
         style: Style,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let symbols_len = symbols.len();
+        let symbols_len = symbols.chars().count();
 
         if symbols_len > u16::MAX as usize {
             panic!()
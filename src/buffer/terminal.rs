@@ -28,6 +28,9 @@ pub struct Terminal<W: Write> {
     /// This is needed to get error when attempt to
     /// restore cursor state when terminal gets dropped fails.
     cursor_visible: bool,
+
+    /// Opens another writer to the same terminal, which restores it when the app panics.
+    panic_writer: Option<fn() -> Box<dyn Write>>,
 }
 
 impl<W: Write> Drop for Terminal<W> {
@@ -46,11 +49,24 @@ impl<W: Write> Terminal<W> {
         Self {
             writer,
             cursor_visible: true,
+            panic_writer: None,
         }
     }
 
     pub fn from_stdout() -> Terminal<io::Stdout> {
-        Terminal::<io::Stdout>::new(stdout())
+        Terminal::<io::Stdout>::new(stdout()).with_panic_writer(|| Box::new(stdout()))
+    }
+
+    /// Sets function which opens another writer to the same terminal, e.g. to stderr
+    /// or to the tty. The writer restores the terminal when the app panics,
+    /// since the writer of the terminal cannot be used by the panic hook.
+    pub fn with_panic_writer(mut self, panic_writer: fn() -> Box<dyn Write>) -> Self {
+        self.panic_writer = Some(panic_writer);
+        self
+    }
+
+    pub fn panic_writer(&self) -> Option<fn() -> Box<dyn Write>> {
+        self.panic_writer
     }
 
     pub fn size(&self) -> Size {
@@ -139,9 +155,9 @@ impl<W: Write> WriteBuffer for Terminal<W> {
         self.set_cursor(position)?;
 
         let mut utf_8: [u8; 4] = [0; 4];
-        symbol.encode_utf8(&mut utf_8);
+        let encoded = symbol.encode_utf8(&mut utf_8);
 
-        match self.writer.write(&utf_8) {
+        match self.writer.write_all(encoded.as_bytes()) {
             Ok(_) => Ok(()),
             Err(error) => Err(Box::new(error)),
         }
@@ -207,7 +223,7 @@ impl<W: Write> WriteBuffer for Terminal<W> {
         self.set_cursor(position)?;
         self.set_style(position, style)?;
 
-        match self.writer.write(symbols.as_bytes()) {
+        match self.writer.write_all(symbols.as_bytes()) {
             Ok(_) => Ok(()),
             Err(error) => Err(Box::new(error)),
         }
@@ -216,6 +232,8 @@ impl<W: Write> WriteBuffer for Terminal<W> {
     fn set_style(&mut self, position: Position, style: Style) -> Result<(), Box<dyn Error>> {
         self.set_cursor(position)?;
 
+        // attributes are additive, so reset the previous ones first
+        self.writer.queue(SetAttribute(Attribute::Reset))?;
         self.writer
             .queue(SetColors(Colors::new(style.foreground, style.background)))?;
         self.writer
@@ -0,0 +1,268 @@
+//! Loading key bindings and styles from TOML configuration files.
+//!
+//! Key bindings are grouped by scopes, which are usually named after visuals,
+//! styles are tables of colors and attributes:
+//!
+//! ```toml
+//! [keys.global]
+//! "Ctrl+Q" = "quit"
+//!
+//! [keys.list]
+//! "g g" = "first"
+//! "Shift+G" = "last"
+//!
+//! [styles.selection]
+//! foreground = "black"
+//! background = "#87afd7"
+//! underline_color = 208
+//! attributes = ["bold", "underlined"]
+//! ```
+//!
+//! Colors are named colors like `dark_red`, hex values `#rrggbb` or ANSI indices from 0 to 255.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt, fs, io,
+    ops::Range,
+    path::Path,
+    str::FromStr,
+};
+
+use serde::Deserialize;
+use toml::{Spanned, Value};
+
+use crate::{
+    input::{KeySequence, Keymap},
+    style::{Attribute, Color, Style},
+};
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The configuration file cannot be read.
+    Io(io::Error),
+    /// The configuration is not valid, line and column are counted from 1.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl ConfigError {
+    fn at(source: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        let before = &source[..span.start.min(source.len())];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        ConfigError::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "cannot read configuration: {error}"),
+            ConfigError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "line {line}, column {column}: {message}"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default)]
+    keys: BTreeMap<String, BTreeMap<Spanned<String>, Spanned<String>>>,
+    #[serde(default)]
+    styles: BTreeMap<String, RawStyle>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawStyle {
+    foreground: Option<Spanned<Value>>,
+    background: Option<Spanned<Value>>,
+    underline_color: Option<Spanned<Value>>,
+    #[serde(default)]
+    attributes: Vec<Spanned<String>>,
+}
+
+/// Key bindings and styles loaded from a configuration.
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    keymaps: HashMap<String, Keymap>,
+    styles: HashMap<String, Style>,
+}
+
+impl Config {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns key bindings of the scope.
+    pub fn keymap(&self, scope: &str) -> Option<&Keymap> {
+        self.keymaps.get(scope)
+    }
+
+    /// Adds key bindings of the scope to the keymap, replacing its conflicting bindings,
+    /// so the configuration can remap default bindings of an application.
+    pub fn apply_keymap(&self, scope: &str, keymap: &mut Keymap) {
+        for binding in self.keymap(scope).map_or(&[][..], Keymap::bindings) {
+            keymap.rebind(binding.keys.clone(), binding.command.clone());
+        }
+    }
+
+    pub fn style(&self, name: &str) -> Option<Style> {
+        self.styles.get(name).copied()
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let raw: RawConfig = toml::from_str(source).map_err(|error| {
+            ConfigError::at(source, error.span().unwrap_or(0..0), error.message().trim())
+        })?;
+
+        let mut config = Config::default();
+
+        for (scope, bindings) in raw.keys {
+            let mut keymap = Keymap::new();
+            let mut bindings: Vec<_> = bindings.into_iter().collect();
+
+            // keep order of the file
+            bindings.sort_by_key(|(keys, _)| keys.span().start);
+
+            for (keys, command) in bindings {
+                KeySequence::from_str(keys.get_ref())
+                    .and_then(|sequence| keymap.bind_sequence(sequence, command.into_inner()))
+                    .map_err(|error| ConfigError::at(source, keys.span(), error.to_string()))?;
+            }
+
+            config.keymaps.insert(scope, keymap);
+        }
+
+        for (name, raw_style) in raw.styles {
+            let mut style = Style::default();
+
+            if let Some(color) = &raw_style.foreground {
+                style.foreground = parse_color(source, color)?;
+            }
+
+            if let Some(color) = &raw_style.background {
+                style.background = parse_color(source, color)?;
+            }
+
+            if let Some(color) = &raw_style.underline_color {
+                style.underline_color = parse_color(source, color)?;
+            }
+
+            for attribute in raw_style.attributes.iter() {
+                style.attributes.set(parse_attribute(source, attribute)?);
+            }
+
+            config.styles.insert(name, style);
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_color(source: &str, value: &Spanned<Value>) -> Result<Color, ConfigError> {
+    let color = match value.get_ref() {
+        Value::Integer(index) => u8::try_from(*index).ok().map(Color::AnsiValue),
+        Value::String(text) => match text.strip_prefix('#') {
+            Some(hex) => parse_hex(hex),
+            None => color_by_name(text),
+        },
+        _ => None,
+    };
+
+    color.ok_or_else(|| {
+        ConfigError::at(
+            source,
+            value.span(),
+            format!(
+                "invalid color {}, expected a color name, `#rrggbb` or ANSI index from 0 to 255",
+                value.get_ref()
+            ),
+        )
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+
+    Some(Color::Rgb {
+        r: component(0)?,
+        g: component(2)?,
+        b: component(4)?,
+    })
+}
+
+fn color_by_name(name: &str) -> Option<Color> {
+    let color = match normalize(name).as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "darkgrey" | "darkgray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "darkred" => Color::DarkRed,
+        "green" => Color::Green,
+        "darkgreen" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "darkyellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "darkblue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "darkmagenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "darkcyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return None,
+    };
+
+    Some(color)
+}
+
+fn parse_attribute(source: &str, name: &Spanned<String>) -> Result<Attribute, ConfigError> {
+    let normalized = normalize(name.get_ref());
+
+    Attribute::iterator()
+        .find(|attribute| format!("{attribute:?}").to_ascii_lowercase() == normalized)
+        .ok_or_else(|| {
+            ConfigError::at(
+                source,
+                name.span(),
+                format!("unknown attribute `{}`", name.get_ref()),
+            )
+        })
+}
+
+/// Lowercases the name and removes separators, so `dark_red`, `dark-red` and `DarkRed` are equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|char| !matches!(char, '_' | '-' | ' '))
+        .map(|char| char.to_ascii_lowercase())
+        .collect()
+}
@@ -1,10 +1,22 @@
 mod app;
+#[cfg(feature = "tokio")]
+mod async_event_loop;
 mod event_loop;
 mod position;
+mod program;
 mod rect;
+mod signal;
 mod size;
+mod task;
+mod terminal_guard;
+mod terminal_modes;
+mod timer;
+mod viewport;
 
+#[cfg(feature = "tokio")]
+pub use async_event_loop::AsyncEventLoop;
+pub use task::{task, Task};
 pub use {
     app::*, crossterm::style::Attribute, crossterm::style::Color, event_loop::*, position::*,
-    rect::*, size::*,
+    program::*, rect::*, signal::Signal, size::*, timer::TimerId, viewport::Viewport,
 };
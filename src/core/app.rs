@@ -1,26 +1,58 @@
 use std::{
+    error::Error,
     io::{self, Write},
     process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    queue,
+    style::{Attribute, SetAttribute},
+    terminal::{Clear, ClearType},
 };
 
 use crate::{
-    buffer::{Terminal, VirtualBuffer},
+    buffer::{clear_uncovered, Terminal, VirtualBuffer},
     input::{
-        Event, KeyEventArgs, KeyEventKind, MouseButtonEventArgs, MouseEventArgs, MouseEventKind,
-        MouseWheelEventArgs, PasteEventArgs,
+        Event, FocusDirection, Gesture, GestureRecognizer, KeyCode, KeyCombination, KeyEventArgs,
+        KeyEventKind, KeyModifiers, KeyboardEnhancementFlags, MouseButtonEventArgs, MouseEvent,
+        MouseEventArgs, MouseEventKind, MouseWheelEventArgs, PasteEventArgs,
     },
-    visual::{MutableContextAction, RetainedMutableContext, Visual},
-    EventLoop, Size,
+    visual::{
+        Invalidation, MutableContext, MutableContextAction, RetainedMutableContext, Visual,
+        VisualId,
+    },
+    EventLoop, Message, Position, Rect, Signal, Size, Task, Viewport,
 };
 
+use super::{terminal_guard::TerminalGuard, terminal_modes::TerminalModes, timer::Timers};
+
+/// Time after the app resumed itself, in which the signal of the continued process
+/// is expected. A later signal comes from another stop of the process.
+const CONTINUE_TIMEOUT: Duration = Duration::from_millis(500);
+
 /// Runs [`Visual`] using the given visual as a root visual
 /// and created [`Terminal`] from stdout.
+///
+/// Errors of the terminal are printed to stderr and reported by the failure exit code,
+/// use [`try_run_app`] to handle them.
 pub fn run_app(visual: impl Visual) -> ExitCode {
+    try_run_app(visual).unwrap_or_else(|error| {
+        eprintln!("{error}");
+
+        ExitCode::FAILURE
+    })
+}
+
+/// Runs [`Visual`] like [`run_app`], but returns errors of the terminal.
+///
+/// The terminal is restored before the function returns, even if it fails.
+pub fn try_run_app(visual: impl Visual) -> io::Result<ExitCode> {
     let terminal = Terminal::<io::Stdout>::from_stdout();
 
     let mut app = VisualApp::new(visual, terminal);
 
-    EventLoop::default().run(&mut app)
+    EventLoop::default().try_run(&mut app)
 }
 
 /// Provides event loop and root mutable context.
@@ -31,21 +63,115 @@ where
 {
     visual: V,
     terminal: Terminal<W>,
-    /// Is the root visual focused or not
+    /// Is the root visual or one of its descendants focused or not
     is_focused: bool,
+    /// Recognizes clicks and drags from mouse events.
+    gestures: GestureRecognizer,
+    /// Timers started by visuals.
+    timers: Timers,
+    /// Tasks spawned by visuals, which are not yet taken by the event loop.
+    tasks: Vec<Task>,
+    /// Messages posted by visuals, which are not yet taken by the event loop.
+    messages: Vec<Message>,
+    /// Time between redraws while the visual is animating.
+    frame_interval: Duration,
+    last_frame: Instant,
+    /// Was the visual animating when it was drawn last time,
+    /// so one more frame is needed to draw the end of the animation.
+    was_animating: bool,
+    /// Area of the terminal where the visual is drawn.
+    viewport: Viewport,
+    /// Size of the visual after the last draw, cells which it does not cover anymore
+    /// are cleared by the next draw.
+    drawn_size: Size,
+    /// Key which suspends the app, unless it is handled by visuals.
+    suspend_key: Option<KeyCombination>,
+    /// The app is already resumed after it suspended itself,
+    /// so the signal of the continued process is ignored until this time.
+    ignore_continue_until: Option<Instant>,
+    /// Top row of the inline viewport.
+    inline_top: u16,
+    /// Size of the terminal, which is updated by resize events.
+    screen_size: Size,
+    /// Size of the last resize event and time when it is applied.
+    pending_resize: Option<(Size, Instant)>,
+    /// Time to wait for more resize events before the last one is applied.
+    resize_debounce: Duration,
+    /// Terminal modes to enable on start.
+    modes: TerminalModes,
+    /// Restores the terminal on exit, on panic and when the app is dropped.
+    terminal_guard: Option<TerminalGuard>,
+    /// Error of the terminal which stopped the app, it is returned on exit.
+    error: Option<io::Error>,
+}
+
+impl<V, W> Drop for VisualApp<V, W>
+where
+    W: Write,
+    V: Visual,
+{
+    fn drop(&mut self) {
+        if let Some(terminal_guard) = self.terminal_guard.take() {
+            // errors cannot be reported from drop
+            let _ = terminal_guard.restore(&mut self.terminal);
+        }
+    }
 }
 
 /// Processes events from [`EventLoop`].
 pub trait EventHandler {
-    /// Processes event loop start.
-    fn on_start(&mut self);
+    /// Processes event loop start, the event loop fails if an error is returned.
+    fn on_start(&mut self) -> io::Result<()>;
 
     /// Processes the given event and returns value indicating
     /// whether event loop should exit or not.
     fn on_event(&mut self, event: &Event) -> Option<ExitCode>;
 
-    /// Processes event loop exit.
-    fn on_exit(&mut self);
+    /// Processes tick of the event loop and returns value indicating
+    /// whether event loop should exit or not.
+    fn on_tick(&mut self) -> Option<ExitCode> {
+        None
+    }
+
+    /// Returns time when [`EventHandler::on_deadline`] should be called,
+    /// the event loop sleeps until it unless an event comes earlier.
+    fn next_deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    /// Processes the deadline returned by [`EventHandler::next_deadline`] and returns
+    /// value indicating whether event loop should exit or not.
+    fn on_deadline(&mut self) -> Option<ExitCode> {
+        None
+    }
+
+    /// Processes the message posted by [`crate::MessageSender`] and returns
+    /// value indicating whether event loop should exit or not.
+    fn on_message(&mut self, _message: Message) -> Option<ExitCode> {
+        None
+    }
+
+    /// Processes the signal of the process and returns value indicating
+    /// whether event loop should exit or not, by default the event loop exits on
+    /// signals which terminate the process.
+    fn on_signal(&mut self, signal: Signal) -> Option<ExitCode> {
+        signal.exit_code()
+    }
+
+    /// Returns futures spawned since the last call, the event loop runs them
+    /// and posts their outputs as messages.
+    fn take_tasks(&mut self) -> Vec<Task> {
+        vec![]
+    }
+
+    /// Returns messages posted since the last call, the event loop delivers them
+    /// to [`EventHandler::on_message`] right after the current event.
+    fn take_messages(&mut self) -> Vec<Message> {
+        vec![]
+    }
+
+    /// Processes event loop exit, it is called even if the event loop fails.
+    fn on_exit(&mut self) -> io::Result<()>;
 }
 
 impl<V, W> EventHandler for VisualApp<V, W>
@@ -53,11 +179,11 @@ where
     W: Write,
     V: Visual,
 {
-    fn on_start(&mut self) {
-        crossterm::terminal::enable_raw_mode().unwrap();
+    fn on_start(&mut self) -> io::Result<()> {
+        self.enter_terminal()?;
 
         // draw for the first time
-        self.redraw();
+        self.redraw()
     }
 
     fn on_event(&mut self, event: &Event) -> Option<ExitCode> {
@@ -65,102 +191,155 @@ where
         let context = &mut RetainedMutableContext::new(&mut actions);
 
         match event {
-            Event::FocusGained => self.visual.on_got_focus(context),
-            Event::FocusLost => self.visual.on_lost_focus(context),
+            Event::FocusGained if self.is_focused => self.visual.on_got_focus(context),
+            Event::FocusLost if self.is_focused => self.visual.on_lost_focus(context),
+            Event::FocusGained | Event::FocusLost => {}
             Event::Key(key_event) => match key_event.kind {
                 KeyEventKind::Press | KeyEventKind::Repeat => {
-                    self.visual
-                        .on_key_press(&KeyEventArgs::from_event(key_event), context);
+                    let args = KeyEventArgs::from_event(key_event);
+                    let handled = self.visual.on_key_press(&args, context);
+
+                    // Tab and Shift+Tab move focus, unless they are handled by visuals
+                    if let Some(direction) = FocusDirection::from_key(&args).filter(|_| !handled) {
+                        self.move_focus(direction, context);
+                    } else if !handled && self.is_suspend_key(&args) {
+                        let result = self.suspend();
+                        self.keep_error(result);
+                    }
                 }
                 KeyEventKind::Release => {
                     self.visual
                         .on_key_release(&KeyEventArgs::from_event(key_event), context);
                 }
             },
-            Event::Mouse(mouse_event) => match mouse_event.kind {
-                MouseEventKind::Down(mouse_button) => {
-                    self.visual.on_mouse_down(
-                        &MouseButtonEventArgs::from_event(mouse_event, mouse_button),
-                        context,
-                    );
-                }
-                MouseEventKind::Up(mouse_button) => {
-                    self.visual.on_mouse_up(
-                        &MouseButtonEventArgs::from_event(mouse_event, mouse_button),
-                        context,
-                    );
-                }
-                MouseEventKind::Drag(_) => {
-                    self.visual
-                        .on_mouse_move(&MouseEventArgs::from_event(mouse_event), context);
-                }
-                MouseEventKind::Moved => {
-                    self.visual
-                        .on_mouse_move(&MouseEventArgs::from_event(mouse_event), context);
-                }
-                MouseEventKind::ScrollDown => {
-                    self.visual.on_mouse_wheel(
-                        &MouseWheelEventArgs::from_event(mouse_event, 1, true),
-                        context,
-                    );
-                }
-                MouseEventKind::ScrollUp => {
-                    self.visual.on_mouse_wheel(
-                        &MouseWheelEventArgs::from_event(mouse_event, -1, true),
-                        context,
-                    );
-                }
-                MouseEventKind::ScrollLeft => {
-                    self.visual.on_mouse_wheel(
-                        &MouseWheelEventArgs::from_event(mouse_event, -1, false),
-                        context,
-                    );
-                }
-                MouseEventKind::ScrollRight => {
-                    self.visual.on_mouse_wheel(
-                        &MouseWheelEventArgs::from_event(mouse_event, 1, false),
-                        context,
-                    );
+            Event::Mouse(mouse_event) => {
+                if let Some(mouse_event) = self.to_viewport(mouse_event) {
+                    self.on_mouse_event(&mouse_event, context);
+
+                    match self.gestures.recognize(&mouse_event) {
+                        Some(Gesture::Click(args)) => self.visual.on_click(&args, context),
+                        Some(Gesture::DragStart(args)) => self.visual.on_drag_start(&args, context),
+                        Some(Gesture::Drag(args)) => self.visual.on_drag(&args, context),
+                        Some(Gesture::DragEnd(args)) => self.visual.on_drag_end(&args, context),
+                        None => false,
+                    };
                 }
-            },
+            }
             Event::Paste(str) => {
                 self.visual.on_paste(&PasteEventArgs::new(str), context);
             }
-            Event::Resize(column, row) => {
-                let desired_size = self
-                    .visual
-                    .measure(Size::new(column.to_owned(), row.to_owned()));
+            Event::Resize(width, height) => {
+                // bursts of resize events are applied at once when they stop
+                self.pending_resize = Some((
+                    Size::new(*width, *height),
+                    Instant::now() + self.resize_debounce,
+                ));
+            }
+        }
 
-                let size = desired_size.clip(self.terminal.size());
+        self.apply_actions(actions)
+    }
 
-                let mut virtual_buffer = VirtualBuffer::new(&mut self.terminal, size.into());
+    fn on_tick(&mut self) -> Option<ExitCode> {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
 
-                self.visual.draw(&mut virtual_buffer, size);
-            }
+        self.visual.on_tick(context);
+
+        self.apply_actions(actions)
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.timers
+            .next_deadline()
+            .into_iter()
+            .chain(self.next_frame())
+            .chain(self.pending_resize.map(|(_, deadline)| deadline))
+            .min()
+    }
+
+    fn on_deadline(&mut self) -> Option<ExitCode> {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+
+        let now = Instant::now();
+        let resize = self
+            .pending_resize
+            .filter(|(_, deadline)| *deadline <= now)
+            .map(|(size, _)| size);
+
+        if let Some(size) = resize {
+            self.pending_resize = None;
+
+            let result = self.resize(size, context);
+            self.keep_error(result);
         }
 
-        let mut exit_code = None;
-        let mut redraw = false;
-        let mut is_focused = self.is_focused;
+        for timer in self.timers.take_due(now) {
+            self.visual.on_timer(timer, context);
+        }
+
+        let exit_code = self.apply_actions(actions);
+
+        let result = if resize.is_some() {
+            self.redraw()
+        } else if self
+            .next_frame()
+            .is_some_and(|frame| frame <= Instant::now())
+        {
+            // only animating visuals are painted
+            self.draw()
+        } else {
+            Ok(())
+        };
+
+        self.keep_error(result).or(exit_code)
+    }
+
+    /// Delivers the message to the visual tree.
+    fn on_message(&mut self, message: Message) -> Option<ExitCode> {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+
+        self.visual.on_message(&*message, context);
+
+        self.apply_actions(actions)
+    }
+
+    /// Prepares the terminal again when the process is continued after it was stopped.
+    fn on_signal(&mut self, signal: Signal) -> Option<ExitCode> {
+        match signal {
+            Signal::Continue => {
+                let is_resumed = self
+                    .ignore_continue_until
+                    .take()
+                    .is_some_and(|deadline| Instant::now() <= deadline);
+
+                if is_resumed {
+                    return None;
+                }
+
+                let result = self.resume();
 
-        for action in actions {
-            match action {
-                MutableContextAction::Redraw => redraw = true,
-                MutableContextAction::SetFocus(focus) => is_focused = focus,
-                MutableContextAction::Terminate(exit_code_val) => exit_code = Some(exit_code_val),
+                self.keep_error(result)
             }
+            _ => signal.exit_code(),
         }
+    }
 
-        if redraw {
-            self.redraw();
-        }
+    fn take_tasks(&mut self) -> Vec<Task> {
+        std::mem::take(&mut self.tasks)
+    }
 
-        self.is_focused = is_focused;
-        exit_code
+    fn take_messages(&mut self) -> Vec<Message> {
+        std::mem::take(&mut self.messages)
     }
 
-    fn on_exit(&mut self) {
-        crossterm::terminal::disable_raw_mode().unwrap();
+    /// Restores the terminal and returns the error which stopped the app, if any.
+    fn on_exit(&mut self) -> io::Result<()> {
+        let result = self.leave_terminal();
+
+        self.error.take().map_or(result, Err)
     }
 }
 
@@ -174,18 +353,498 @@ where
             visual,
             terminal,
             is_focused: false,
+            gestures: GestureRecognizer::new(),
+            timers: Timers::default(),
+            tasks: vec![],
+            messages: vec![],
+            frame_interval: Duration::from_millis(33),
+            last_frame: Instant::now(),
+            was_animating: false,
+            viewport: Viewport::default(),
+            drawn_size: Size::default(),
+            suspend_key: Some(KeyCombination::new(
+                KeyCode::Char('z'),
+                KeyModifiers::CONTROL,
+            )),
+            ignore_continue_until: None,
+            inline_top: 0,
+            screen_size: Size::default(),
+            pending_resize: None,
+            resize_debounce: Duration::from_millis(50),
+            modes: TerminalModes::default(),
+            terminal_guard: None,
+            error: None,
         }
     }
 
-    pub fn redraw(&mut self) {
-        let available_size = self.terminal.size();
+    /// Sets whether mouse buttons, wheel and motion are reported, enabled by default.
+    pub fn with_mouse_capture(mut self, mouse_capture: bool) -> Self {
+        self.modes.mouse_capture = mouse_capture;
+        self
+    }
+
+    /// Sets whether pasted text is reported as a paste event, enabled by default.
+    pub fn with_bracketed_paste(mut self, bracketed_paste: bool) -> Self {
+        self.modes.bracketed_paste = bracketed_paste;
+        self
+    }
+
+    /// Sets whether terminal focus changes are reported, enabled by default.
+    pub fn with_focus_change(mut self, focus_change: bool) -> Self {
+        self.modes.focus_change = focus_change;
+        self
+    }
+
+    /// Sets keyboard enhancement flags which are pushed on start
+    /// when the terminal supports them, disabled by default.
+    pub fn with_keyboard_enhancement(mut self, flags: KeyboardEnhancementFlags) -> Self {
+        self.modes.keyboard_enhancement = Some(flags);
+        self
+    }
+
+    /// Sets how often animating visuals are redrawn, 30 frames per second by default.
+    pub fn with_frame_rate(mut self, frames_per_second: u32) -> Self {
+        self.frame_interval = Duration::from_secs(1) / frames_per_second.max(1);
+        self
+    }
+
+    /// Sets key which suspends the app like in a shell, Ctrl+Z by default.
+    /// The key is ignored if it is handled by visuals.
+    pub fn with_suspend_key(mut self, key: Option<KeyCombination>) -> Self {
+        self.suspend_key = key;
+        self
+    }
+
+    /// Sets area of the terminal where the visual is drawn, the whole alternate screen by default.
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = viewport;
+        self.modes.alternate_screen = viewport == Viewport::Fullscreen;
+        self
+    }
+
+    /// Sets time to wait for more resize events before the terminal is redrawn
+    /// in the new size, 50 milliseconds by default.
+    pub fn with_resize_debounce(mut self, resize_debounce: Duration) -> Self {
+        self.resize_debounce = resize_debounce;
+        self
+    }
+
+    /// Returns size of the terminal, which is known to the app.
+    pub fn screen_size(&self) -> Size {
+        self.screen_size
+    }
+
+    /// Sets maximum time between clicks which are counted as a double-click.
+    pub fn with_click_interval(mut self, click_interval: Duration) -> Self {
+        self.gestures = GestureRecognizer::new().with_click_interval(click_interval);
+        self
+    }
+
+    pub fn visual(&self) -> &V {
+        &self.visual
+    }
+
+    /// Replaces the root visual, it is measured and drawn over the previous one
+    /// if the app is running, cells which the new visual does not cover are cleared.
+    pub fn set_visual(&mut self, visual: V) -> io::Result<()> {
+        self.visual = visual;
+
+        if self.terminal_guard.is_none() {
+            return Ok(());
+        }
+
+        self.visual.invalidate(Invalidation::Measure);
+        self.draw()
+    }
+
+    /// Finds the visual with the given id and downcasts it to the given type.
+    pub fn find<T: Visual>(&self, id: impl Into<VisualId>) -> Option<&T> {
+        (&self.visual as &dyn Visual).find(id)
+    }
+
+    /// Finds the visual with the given id and downcasts it to the given type for mutation,
+    /// it is measured and painted by the next draw, e.g. [`VisualApp::redraw`].
+    pub fn find_mut<T: Visual>(&mut self, id: impl Into<VisualId>) -> Option<&mut T> {
+        (&mut self.visual as &mut dyn Visual).find_mut(id)
+    }
+
+    /// Measures and draws the whole visual again, e.g. after the terminal was cleared.
+    pub fn redraw(&mut self) -> io::Result<()> {
+        self.visual.invalidate(Invalidation::Measure);
+        self.visual.invalidate(Invalidation::Render);
+        self.draw()
+    }
+
+    /// Draws the visual, only invalid parts of the visual tree are painted.
+    fn draw(&mut self) -> io::Result<()> {
+        let viewport = self.viewport_rect();
+        let available_size = Size::from(viewport);
 
         let desired_size = self.visual.measure(available_size);
 
-        let draw_size = desired_size.clip(available_size);
+        let draw_size = self.visual.arrange(desired_size.clip(available_size));
+
+        let stale_rects = [Rect::from(self.drawn_size).intersection(Rect::from(available_size))];
+        let covered = [Rect::from(draw_size)];
+
+        match self.viewport {
+            Viewport::Fullscreen => {
+                clear_uncovered(&mut self.terminal, &stale_rects, &covered)
+                    .map_err(buffer_error)?;
+                self.visual.render(&mut self.terminal, draw_size);
+            }
+            Viewport::Inline(_) => {
+                let buffer = &mut VirtualBuffer::new(&mut self.terminal, viewport);
+
+                clear_uncovered(buffer, &stale_rects, &covered).map_err(buffer_error)?;
+                self.visual.render(buffer, draw_size);
+            }
+        };
+
+        self.drawn_size = draw_size;
+
+        self.last_frame = Instant::now();
+        self.was_animating = self.visual.is_animating();
+
+        self.terminal.flush()
+    }
+
+    /// Restores the terminal and stops the process like Ctrl+Z in a shell,
+    /// the terminal is prepared and drawn again when the process is continued.
+    /// Nothing happens on platforms without job control.
+    pub fn suspend(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            if let Some(terminal_guard) = self.terminal_guard.take() {
+                terminal_guard.restore(&mut self.terminal)?;
+            }
+
+            // returns when the process is continued, or at once if the shell has no job control
+            signal_hook::low_level::raise(signal_hook::consts::SIGTSTP)?;
+
+            self.resume()?;
+
+            // no signal comes if the process was not stopped
+            self.ignore_continue_until = Some(Instant::now() + CONTINUE_TIMEOUT);
+        }
+
+        Ok(())
+    }
+
+    /// Prepares the terminal again and draws the visual, e.g. after the process was stopped.
+    /// The terminal is restored first, since the state of a stopped process may be lost.
+    pub fn resume(&mut self) -> io::Result<()> {
+        if let Some(terminal_guard) = self.terminal_guard.take() {
+            terminal_guard.restore(&mut self.terminal)?;
+        }
+
+        self.enter_terminal()?;
+        self.redraw()
+    }
+
+    fn is_suspend_key(&self, args: &KeyEventArgs) -> bool {
+        self.suspend_key == Some(KeyCombination::from_args(args))
+    }
+
+    /// Enables the terminal modes and places the inline viewport below the cursor.
+    fn enter_terminal(&mut self) -> io::Result<()> {
+        let panic_writer = self.terminal.panic_writer();
+
+        self.terminal_guard = Some(TerminalGuard::new(
+            self.modes,
+            &mut self.terminal,
+            panic_writer,
+        )?);
+        self.screen_size = self.terminal.size();
+
+        if let Viewport::Inline(_) = self.viewport {
+            let cursor = self.terminal.get_cursor()?;
+
+            // the viewport starts on a new line
+            let row = if cursor.x > 0 { cursor.y + 1 } else { cursor.y };
+
+            self.place_inline_viewport(row)?;
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the inline viewport in the history and restores the terminal.
+    fn leave_terminal(&mut self) -> io::Result<()> {
+        if let Viewport::Inline(_) = self.viewport {
+            // keep the last frame in the history and continue the output below it
+            let viewport = self.viewport_rect();
+
+            self.terminal
+                .set_cursor(Position::new(0, viewport.bottom().saturating_sub(1)))?;
+            self.terminal.write_all(b"\r\n")?;
+            self.terminal.flush()?;
+        }
+
+        match self.terminal_guard.take() {
+            Some(terminal_guard) => terminal_guard.restore(&mut self.terminal),
+            None => Ok(()),
+        }
+    }
+
+    /// Prints the text above the inline viewport, so it scrolls into the terminal history
+    /// like a normal output of the program. Nothing is printed in the full-screen viewport.
+    pub fn print_above(&mut self, text: &str) -> io::Result<()> {
+        let Viewport::Inline(_) = self.viewport else {
+            return Ok(());
+        };
+
+        let screen = self.screen_size;
+        let mut row = self.viewport_rect().y;
+
+        // the text replaces the viewport, which is drawn again below it
+        self.terminal.set_cursor(Position::new(0, row))?;
+        queue!(
+            self.terminal,
+            SetAttribute(Attribute::Reset),
+            Clear(ClearType::FromCursorDown)
+        )?;
+
+        for line in text.lines() {
+            let width = line.chars().count() as u16;
+            let rows = width.div_ceil(screen.width.max(1)).max(1);
+
+            self.terminal.write_all(line.as_bytes())?;
+            self.terminal.write_all(b"\r\n")?;
+
+            // the screen scrolls when the text reaches the bottom
+            row = row
+                .saturating_add(rows)
+                .min(screen.height.saturating_sub(1));
+        }
+
+        self.place_inline_viewport(row)?;
+        self.redraw()
+    }
+
+    /// Places the inline viewport at the row, the screen is scrolled up
+    /// if the viewport does not fit below it.
+    fn place_inline_viewport(&mut self, row: u16) -> io::Result<()> {
+        let screen = self.screen_size;
+        let height = self.inline_height(screen);
+        let scroll = row.saturating_add(height).saturating_sub(screen.height);
+
+        if scroll > 0 {
+            // new lines at the bottom of the screen move the content above into the history
+            self.terminal
+                .set_cursor(Position::new(0, screen.height.saturating_sub(1)))?;
+            self.terminal.write_all(&vec![b'\n'; scroll as usize])?;
+        }
+
+        self.inline_top = row.saturating_sub(scroll);
 
-        self.visual.draw(&mut self.terminal, draw_size);
+        self.terminal
+            .set_cursor(Position::new(0, self.inline_top))?;
+        queue!(self.terminal, Clear(ClearType::FromCursorDown))
+    }
+
+    fn inline_height(&self, screen: Size) -> u16 {
+        match self.viewport {
+            Viewport::Fullscreen => screen.height,
+            Viewport::Inline(lines) => lines.min(screen.height),
+        }
+    }
+
+    /// Applies the new size of the terminal, the viewport is cleared,
+    /// since its content is no longer valid, and visuals are notified.
+    fn resize(&mut self, size: Size, context: &mut dyn MutableContext) -> io::Result<()> {
+        self.screen_size = size;
+
+        let viewport = self.viewport_rect();
+
+        self.terminal.set_cursor(Position::new(0, viewport.y))?;
+        queue!(
+            self.terminal,
+            SetAttribute(Attribute::Reset),
+            Clear(ClearType::FromCursorDown)
+        )?;
+
+        self.visual.on_resize(viewport.into(), context);
+
+        Ok(())
+    }
+
+    /// Returns area of the terminal where the visual is drawn.
+    fn viewport_rect(&self) -> Rect {
+        let screen = self.screen_size;
+
+        match self.viewport {
+            Viewport::Fullscreen => screen.into(),
+            Viewport::Inline(_) => {
+                let height = self.inline_height(screen);
+
+                // the viewport is kept on the screen when the terminal shrinks
+                let top = self.inline_top.min(screen.height - height);
+
+                Rect::new(0, top, screen.width, height)
+            }
+        }
+    }
+
+    /// Translates the mouse event to coordinates of the viewport, events above
+    /// the inline viewport are dropped except ones which finish a gesture.
+    fn to_viewport(&self, mouse_event: &MouseEvent) -> Option<MouseEvent> {
+        let top = self.viewport_rect().y;
+
+        let is_finishing = matches!(
+            mouse_event.kind,
+            MouseEventKind::Drag(_) | MouseEventKind::Up(_)
+        );
+
+        (mouse_event.row >= top || is_finishing).then(|| MouseEvent {
+            row: mouse_event.row.saturating_sub(top),
+            ..*mouse_event
+        })
+    }
+
+    /// Returns time of the next animation frame, if the visual is animating.
+    fn next_frame(&self) -> Option<Instant> {
+        (self.was_animating || self.visual.is_animating())
+            .then_some(self.last_frame + self.frame_interval)
+    }
+
+    /// Delivers the raw mouse event to the root visual.
+    fn on_mouse_event(&mut self, mouse_event: &MouseEvent, context: &mut dyn MutableContext) {
+        match mouse_event.kind {
+            MouseEventKind::Down(mouse_button) => {
+                self.visual.on_mouse_down(
+                    &MouseButtonEventArgs::from_event(mouse_event, mouse_button),
+                    context,
+                );
+            }
+            MouseEventKind::Up(mouse_button) => {
+                self.visual.on_mouse_up(
+                    &MouseButtonEventArgs::from_event(mouse_event, mouse_button),
+                    context,
+                );
+            }
+            MouseEventKind::Drag(_) => {
+                self.visual
+                    .on_mouse_move(&MouseEventArgs::from_event(mouse_event), context);
+            }
+            MouseEventKind::Moved => {
+                self.visual
+                    .on_mouse_move(&MouseEventArgs::from_event(mouse_event), context);
+            }
+            MouseEventKind::ScrollDown => {
+                self.visual.on_mouse_wheel(
+                    &MouseWheelEventArgs::from_event(mouse_event, 1, true),
+                    context,
+                );
+            }
+            MouseEventKind::ScrollUp => {
+                self.visual.on_mouse_wheel(
+                    &MouseWheelEventArgs::from_event(mouse_event, -1, true),
+                    context,
+                );
+            }
+            MouseEventKind::ScrollLeft => {
+                self.visual.on_mouse_wheel(
+                    &MouseWheelEventArgs::from_event(mouse_event, -1, false),
+                    context,
+                );
+            }
+            MouseEventKind::ScrollRight => {
+                self.visual.on_mouse_wheel(
+                    &MouseWheelEventArgs::from_event(mouse_event, 1, false),
+                    context,
+                );
+            }
+        }
+    }
+
+    /// Moves focus to the next focusable visual in the given direction,
+    /// wrapping around at the ends of the tree.
+    fn move_focus(&mut self, direction: FocusDirection, visual_context: &mut dyn MutableContext) {
+        let moved = self.is_focused && self.visual.focus_next(direction, visual_context);
+
+        self.is_focused = moved || self.visual.focus_first(direction, visual_context);
+
+        visual_context.redraw();
+    }
+
+    /// Applies actions requested by visuals through [`RetainedMutableContext`]
+    /// and returns exit code if termination was requested.
+    fn apply_actions(&mut self, mut actions: Vec<MutableContextAction>) -> Option<ExitCode> {
+        let mut exit_code = None;
+        let mut redraw = false;
+        let mut draw = false;
+
+        // focus changes notify visuals, which can request more actions
+        while !actions.is_empty() {
+            let mut next_actions = vec![];
+            let context = &mut RetainedMutableContext::new(&mut next_actions);
+
+            for action in actions {
+                match action {
+                    MutableContextAction::Redraw => redraw = true,
+                    MutableContextAction::Invalidate(invalidation) => {
+                        self.visual.invalidate(invalidation);
+                        draw = true;
+                    }
+                    MutableContextAction::SetFocus(true) if !self.is_focused => {
+                        self.is_focused = true;
+                        self.visual.on_got_focus(context);
+                    }
+                    MutableContextAction::SetFocus(false) if self.is_focused => {
+                        self.is_focused = false;
+                        self.visual.clear_focus(context);
+                    }
+                    MutableContextAction::SetFocus(_) => {}
+                    // the root visual receives all mouse events anyway
+                    MutableContextAction::SetMouseCapture(_) => {}
+                    MutableContextAction::StartTimer {
+                        timer,
+                        delay,
+                        repeat,
+                    } => self.timers.start(timer, delay, repeat),
+                    MutableContextAction::StopTimer(timer) => self.timers.stop(timer),
+                    MutableContextAction::Spawn(task) => self.tasks.push(task),
+                    MutableContextAction::Post(message) => self.messages.push(message),
+                    MutableContextAction::PrintAbove(text) => {
+                        let result = self.print_above(&text);
+                        self.keep_error(result);
+                    }
+                    MutableContextAction::Terminate(exit_code_val) => {
+                        exit_code = Some(exit_code_val)
+                    }
+                }
+            }
+
+            actions = next_actions;
+        }
+
+        let result = if redraw {
+            self.redraw()
+        } else if draw {
+            self.draw()
+        } else {
+            Ok(())
+        };
+
+        self.keep_error(result).or(exit_code)
+    }
+
+    /// Keeps the first error of the terminal, so the app exits with it,
+    /// and returns the failure exit code if there is an error.
+    pub(crate) fn keep_error(&mut self, result: io::Result<()>) -> Option<ExitCode> {
+        if let Err(error) = result {
+            self.error.get_or_insert(error);
+        }
+
+        self.error.as_ref().map(|_| ExitCode::FAILURE)
+    }
+}
 
-        self.terminal.flush().unwrap();
+/// Converts the error of the buffer, which is usually the error of the terminal.
+fn buffer_error(error: Box<dyn Error>) -> io::Error {
+    match error.downcast::<io::Error>() {
+        Ok(error) => *error,
+        Err(error) => io::Error::other(error.to_string()),
     }
 }
@@ -0,0 +1,150 @@
+use std::{io, process::ExitCode, time::Duration};
+
+use crossterm::event::EventStream;
+use futures_util::StreamExt;
+use tokio::{sync::mpsc, time};
+
+use crate::{EventHandler, Message, MessageSender};
+
+use super::event_loop::Schedule;
+#[cfg(unix)]
+use super::signal::SignalListener;
+
+/// Event loop which runs on the tokio runtime, so the handler can spawn futures
+/// which await I/O without blocking the terminal input.
+///
+/// Tasks spawned by the handler are spawned on the runtime
+/// and their outputs are posted into the loop as messages.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{io, process::ExitCode};
+///
+/// use termrs::{buffer::Terminal, visual::TextBlock, AsyncEventLoop, VisualApp};
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() -> ExitCode {
+///     let mut app = VisualApp::new(TextBlock::new("Hello"), Terminal::<io::Stdout>::from_stdout());
+///
+///     AsyncEventLoop::default().run(&mut app).await
+/// }
+/// ```
+pub struct AsyncEventLoop {
+    /// Interval of ticks, `None` if the loop does not tick.
+    tick_rate: Option<Duration>,
+    sender: mpsc::UnboundedSender<Message>,
+    receiver: mpsc::UnboundedReceiver<Message>,
+}
+
+impl Default for AsyncEventLoop {
+    fn default() -> Self {
+        Self::without_ticks()
+    }
+}
+
+impl AsyncEventLoop {
+    /// Creates event loop which calls [`EventHandler::on_tick`] at the tick rate,
+    /// the default event loop does not tick.
+    pub fn new(tick_rate: Duration) -> Self {
+        Self::with_tick_rate(Some(tick_rate))
+    }
+
+    /// Creates event loop without ticks, which sleeps until the next event, message
+    /// or deadline of the [`EventHandler`].
+    pub fn without_ticks() -> Self {
+        Self::with_tick_rate(None)
+    }
+
+    fn with_tick_rate(tick_rate: Option<Duration>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        Self {
+            tick_rate,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Returns handle which posts messages into the loop from any thread or task.
+    pub fn message_sender(&self) -> MessageSender {
+        let sender = self.sender.clone();
+
+        MessageSender::new(move |message| sender.send(message).is_ok())
+    }
+
+    /// Runs the event loop until the handler requests exit,
+    /// it must be awaited within the tokio runtime.
+    ///
+    /// Errors of the terminal are printed to stderr and reported by the failure exit code,
+    /// use [`AsyncEventLoop::try_run`] to handle them.
+    pub async fn run(&mut self, app: &mut impl EventHandler) -> ExitCode {
+        self.try_run(app).await.unwrap_or_else(|error| {
+            eprintln!("{error}");
+
+            ExitCode::FAILURE
+        })
+    }
+
+    /// Runs the event loop until the handler requests exit or reading of terminal events fails.
+    ///
+    /// [`EventHandler::on_exit`] is called in both cases.
+    pub async fn try_run(&mut self, app: &mut impl EventHandler) -> io::Result<ExitCode> {
+        #[allow(unused_variables)]
+        let (signal_sender, mut signals) = mpsc::unbounded_channel();
+
+        #[cfg(unix)]
+        let signal_listener =
+            SignalListener::spawn(move |signal| signal_sender.send(signal).is_ok())?;
+
+        app.on_start()?;
+
+        let mut events = EventStream::new();
+        let mut schedule = Schedule::new(self.tick_rate);
+
+        let result = loop {
+            self.spawn_tasks(app);
+
+            let exit_code = tokio::select! {
+                Some(event) = events.next() => match event {
+                    Ok(event) => app.on_event(&event),
+                    Err(error) => break Err(error),
+                },
+                // the loop owns a sender, so the channel cannot be closed
+                Some(message) = self.receiver.recv() => app.on_message(message),
+                Some(signal) = signals.recv() => app.on_signal(signal),
+                _ = time::sleep(schedule.timeout(app)) => None,
+            };
+
+            // if exit requested
+            if let Some(exit_code) = schedule.process(app, exit_code) {
+                // stop the event loop.
+                break Ok(exit_code);
+            }
+        };
+
+        #[cfg(unix)]
+        drop(signal_listener);
+
+        let exit_result = app.on_exit();
+
+        // drop messages which came after the exit
+        while self.receiver.try_recv().is_ok() {}
+
+        let exit_code = result?;
+        exit_result?;
+
+        Ok(exit_code)
+    }
+
+    fn spawn_tasks(&self, app: &mut impl EventHandler) {
+        for task in app.take_tasks() {
+            let sender = self.sender.clone();
+
+            tokio::spawn(async move {
+                // the loop may have exited, then the output is dropped
+                let _ = sender.send(task.await);
+            });
+        }
+    }
+}
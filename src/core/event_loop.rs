@@ -1,52 +1,277 @@
 use std::{
+    any::Any,
+    io,
     process::ExitCode,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread,
     time::{Duration, Instant},
 };
 
-use crate::{input, EventHandler};
+use crate::{
+    input::{self, Event},
+    EventHandler,
+};
+
+#[cfg(unix)]
+use super::signal::SignalListener;
+use super::{signal::Signal, task};
+
+/// Time to wait for an event when there are no ticks and deadlines,
+/// the wait is simply repeated after it.
+pub(crate) const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time the input thread waits for terminal events before it checks whether the loop has exited.
+const INPUT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Message posted into the event loop by [`MessageSender`].
+pub type Message = Box<dyn Any + Send>;
+
+enum LoopEvent {
+    Input(io::Result<Event>),
+    Message(Message),
+    Signal(Signal),
+}
+
+/// Thread-safe handle which posts messages into the event loop,
+/// they are delivered to [`EventHandler::on_message`] as soon as possible.
+#[derive(Clone)]
+pub struct MessageSender {
+    send: Arc<dyn Fn(Message) -> bool + Send + Sync>,
+}
+
+impl MessageSender {
+    pub(crate) fn new(send: impl Fn(Message) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            send: Arc::new(send),
+        }
+    }
+
+    /// Posts the message, returns `false` if the event loop does not exist anymore.
+    pub fn send(&self, message: impl Any + Send) -> bool {
+        self.send_boxed(Box::new(message))
+    }
+
+    pub(crate) fn send_boxed(&self, message: Message) -> bool {
+        (self.send)(message)
+    }
+}
+
+/// Ticks of the event loop and deadlines of the [`EventHandler`].
+pub(crate) struct Schedule {
+    /// Interval of ticks, `None` if the loop does not tick.
+    tick_rate: Option<Duration>,
+    next_tick: Option<Instant>,
+}
+
+impl Schedule {
+    pub fn new(tick_rate: Option<Duration>) -> Self {
+        Self {
+            tick_rate,
+            next_tick: tick_rate.map(|tick_rate| Instant::now() + tick_rate),
+        }
+    }
+
+    /// Returns time when the loop should wake up if there are no events.
+    pub fn wake_up(&self, app: &impl EventHandler) -> Option<Instant> {
+        self.next_tick.into_iter().chain(app.next_deadline()).min()
+    }
+
+    /// Returns time to wait for events.
+    pub fn timeout(&self, app: &impl EventHandler) -> Duration {
+        self.wake_up(app).map_or(IDLE_TIMEOUT, |wake_up| {
+            wake_up.saturating_duration_since(Instant::now())
+        })
+    }
+
+    /// Calls the tick and the deadline of the handler if they are due, then
+    /// delivers messages posted by the handler, unless exit is already requested.
+    pub fn process(
+        &mut self,
+        app: &mut impl EventHandler,
+        mut exit_code: Option<ExitCode>,
+    ) -> Option<ExitCode> {
+        if let Some(tick_rate) = self.tick_rate.filter(|_| exit_code.is_none()) {
+            if self.next_tick.is_some_and(|tick| tick <= Instant::now()) {
+                self.next_tick = Some(Instant::now() + tick_rate);
+                exit_code = app.on_tick();
+            }
+        }
+
+        let is_deadline_due = app
+            .next_deadline()
+            .is_some_and(|deadline| deadline <= Instant::now());
+
+        if exit_code.is_none() && is_deadline_due {
+            exit_code = app.on_deadline();
+        }
+
+        // delivered messages may post further messages
+        while exit_code.is_none() {
+            let messages = app.take_messages();
+            if messages.is_empty() {
+                break;
+            }
+
+            exit_code = messages
+                .into_iter()
+                .find_map(|message| app.on_message(message));
+        }
+
+        exit_code
+    }
+}
 
 pub struct EventLoop {
-    tick_rate: Duration,
+    /// Interval of ticks, `None` if the loop does not tick.
+    tick_rate: Option<Duration>,
+    /// Sender of terminal events and messages, which is cloned for the input thread and senders.
+    sender: Sender<LoopEvent>,
+    receiver: Receiver<LoopEvent>,
 }
 
 impl Default for EventLoop {
     fn default() -> Self {
-        Self {
-            tick_rate: Duration::from_millis(250),
-        }
+        Self::without_ticks()
     }
 }
 
 impl EventLoop {
+    /// Creates event loop which calls [`EventHandler::on_tick`] at the tick rate,
+    /// the default event loop does not tick.
     pub fn new(tick_rate: Duration) -> Self {
-        Self { tick_rate }
+        Self::with_tick_rate(Some(tick_rate))
     }
 
+    /// Creates event loop without ticks, which sleeps until the next event
+    /// or deadline of the [`EventHandler`], e.g. a due timer.
+    pub fn without_ticks() -> Self {
+        Self::with_tick_rate(None)
+    }
+
+    fn with_tick_rate(tick_rate: Option<Duration>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            tick_rate,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Returns handle which posts messages into the loop from any thread.
+    pub fn message_sender(&self) -> MessageSender {
+        let sender = self.sender.clone();
+
+        MessageSender::new(move |message| sender.send(LoopEvent::Message(message)).is_ok())
+    }
+
+    /// Runs the event loop until the handler requests exit.
+    ///
+    /// Every task spawned by the handler is polled on its own thread until it completes,
+    /// so tasks must be runtime-agnostic futures, e.g. channels or thread-based work,
+    /// and not futures which need the reactor of an async runtime, like tokio timers
+    /// or sockets. Use `AsyncEventLoop` of the `tokio` feature to spawn such futures.
+    ///
+    /// Errors of the terminal are printed to stderr and reported by the failure exit code,
+    /// use [`EventLoop::try_run`] to handle them.
     pub fn run(&self, app: &mut impl EventHandler) -> ExitCode {
-        app.on_start();
+        self.try_run(app).unwrap_or_else(|error| {
+            eprintln!("{error}");
 
-        let mut last_tick = Instant::now();
+            ExitCode::FAILURE
+        })
+    }
 
-        loop {
-            let timeout = self.tick_rate.saturating_sub(last_tick.elapsed());
+    /// Runs the event loop until the handler requests exit or reading of terminal events fails.
+    ///
+    /// [`EventHandler::on_exit`] is called in both cases.
+    pub fn try_run(&self, app: &mut impl EventHandler) -> io::Result<ExitCode> {
+        #[cfg(unix)]
+        let signal_listener = {
+            let sender = self.sender.clone();
 
-            if input::poll(timeout).unwrap() {
-                let event = input::read().unwrap();
+            SignalListener::spawn(move |signal| sender.send(LoopEvent::Signal(signal)).is_ok())?
+        };
 
-                let exit_code = app.on_event(&event);
+        app.on_start()?;
 
-                // if exit requested
-                if let Some(exit_code) = exit_code {
-                    app.on_exit();
+        let is_running = Arc::new(AtomicBool::new(true));
+        let input_thread = self.spawn_input_thread(is_running.clone());
 
-                    // stop the event loop.
-                    break exit_code;
-                }
-            }
+        let mut schedule = Schedule::new(self.tick_rate);
+
+        let result = loop {
+            self.spawn_tasks(app);
 
-            if last_tick.elapsed() >= self.tick_rate {
-                last_tick = Instant::now();
+            let exit_code = match self.receiver.recv_timeout(schedule.timeout(app)) {
+                Ok(LoopEvent::Input(Ok(event))) => app.on_event(&event),
+                Ok(LoopEvent::Input(Err(error))) => break Err(error),
+                Ok(LoopEvent::Message(message)) => app.on_message(message),
+                Ok(LoopEvent::Signal(signal)) => app.on_signal(signal),
+                Err(RecvTimeoutError::Timeout) => None,
+                // the loop owns a sender, so the channel cannot be disconnected
+                Err(RecvTimeoutError::Disconnected) => unreachable!(),
+            };
+
+            // if exit requested
+            if let Some(exit_code) = schedule.process(app, exit_code) {
+                // stop the event loop.
+                break Ok(exit_code);
             }
+        };
+
+        is_running.store(false, Ordering::Relaxed);
+
+        // a panic of the input thread is already reported by the panic hook
+        let _ = input_thread.join();
+
+        #[cfg(unix)]
+        drop(signal_listener);
+
+        let exit_result = app.on_exit();
+
+        // drop events which came after the exit
+        while self.receiver.try_recv().is_ok() {}
+
+        let exit_code = result?;
+        exit_result?;
+
+        Ok(exit_code)
+    }
+
+    /// Spawns thread per task which blocks on it and posts its output,
+    /// see [`EventLoop::run`] for requirements to the tasks.
+    fn spawn_tasks(&self, app: &mut impl EventHandler) {
+        for task in app.take_tasks() {
+            let sender = self.message_sender();
+
+            thread::spawn(move || sender.send_boxed(task::block_on(task)));
         }
     }
+
+    /// Spawns thread which reads terminal events and sends them into the loop
+    /// while it is running.
+    fn spawn_input_thread(&self, is_running: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+        let sender = self.sender.clone();
+
+        thread::spawn(move || {
+            while is_running.load(Ordering::Relaxed) {
+                let event = match input::poll(INPUT_POLL_TIMEOUT) {
+                    Ok(false) => continue,
+                    Ok(true) => input::read(),
+                    Err(error) => Err(error),
+                };
+
+                let is_error = event.is_err();
+
+                if sender.send(LoopEvent::Input(event)).is_err() || is_error {
+                    break;
+                }
+            }
+        })
+    }
 }
@@ -38,6 +38,15 @@ impl Position {
     pub const fn new(x: u16, y: u16) -> Self {
         Position { x, y }
     }
+
+    /// Returns the position relative to the given origin, coordinates which are less than
+    /// the origin are clamped to zero.
+    pub const fn relative_to(self, origin: Position) -> Self {
+        Position {
+            x: self.x.saturating_sub(origin.x),
+            y: self.y.saturating_sub(origin.y),
+        }
+    }
 }
 
 impl From<(u16, u16)> for Position {
@@ -0,0 +1,315 @@
+use std::{
+    any::Any,
+    collections::VecDeque,
+    future::Future,
+    io::{self, Write},
+    process::ExitCode,
+    time::Instant,
+};
+
+use crate::{
+    buffer::Terminal, input::Event, task, visual::Visual, EventHandler, EventLoop, Message, Signal,
+    Task, VisualApp,
+};
+
+type Update<M, Msg> = dyn FnMut(&mut M, Msg) -> Command<Msg>;
+
+type Subscription<M, Msg> = dyn Fn(&M, &Event) -> Option<Msg>;
+
+/// Effects which are requested by the update function of [`Program`]
+/// and run by the program after the update.
+pub struct Command<Msg> {
+    effects: Vec<Effect<Msg>>,
+}
+
+enum Effect<Msg> {
+    Message(Msg),
+    Task(Task),
+    Exit(ExitCode),
+}
+
+impl<Msg> Default for Command<Msg> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<Msg> Command<Msg> {
+    pub fn none() -> Self {
+        Self { effects: vec![] }
+    }
+
+    /// Updates the model with the message right after the current update.
+    pub fn message(message: Msg) -> Self {
+        Self::effect(Effect::Message(message))
+    }
+
+    /// Runs the future in the background, the model is updated with its output
+    /// when it completes.
+    pub fn perform<F>(future: F) -> Self
+    where
+        F: Future<Output = Msg> + Send + 'static,
+        Msg: Send + 'static,
+    {
+        Self::effect(Effect::Task(task(future)))
+    }
+
+    /// Exits the program with the exit code.
+    pub fn exit(exit_code: ExitCode) -> Self {
+        Self::effect(Effect::Exit(exit_code))
+    }
+
+    /// Combines the commands, their effects run in the given order.
+    pub fn batch(commands: impl IntoIterator<Item = Command<Msg>>) -> Self {
+        Self {
+            effects: commands
+                .into_iter()
+                .flat_map(|command| command.effects)
+                .collect(),
+        }
+    }
+
+    fn effect(effect: Effect<Msg>) -> Self {
+        Self {
+            effects: vec![effect],
+        }
+    }
+}
+
+/// Runs the program built from the model, the update and the view functions
+/// and created [`Terminal`] from stdout, see [`Program`].
+///
+/// Errors of the terminal are printed to stderr and reported by the failure exit code.
+pub fn run_program<M, Msg, V>(
+    model: M,
+    update: impl FnMut(&mut M, Msg) -> Command<Msg> + 'static,
+    view: impl Fn(&M) -> V + 'static,
+) -> ExitCode
+where
+    M: 'static,
+    Msg: Any + Send,
+    V: Visual,
+{
+    let terminal = Terminal::<io::Stdout>::from_stdout();
+
+    let mut program = Program::new(model, update, view, terminal);
+
+    EventLoop::default().run(&mut program)
+}
+
+/// Runs the application in the model-update-view style.
+///
+/// The model is changed only by the update function, which processes messages and
+/// returns [`Command`] with further effects. After messages are processed, the view
+/// function builds the visual tree from the model again and the new tree takes
+/// focus, scroll offsets and other state of visuals from the previous one,
+/// see [`Visual::reconcile`].
+///
+/// Messages come from:
+/// - input events mapped by [`Program::with_subscription`],
+/// - visuals, which post them to the event loop,
+///   e.g. `visual_context.post(Box::new(Msg::Save))`,
+/// - futures of [`Command::perform`].
+///
+/// Other messages are delivered to the visual tree as usual.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::io;
+///
+/// use termrs::{
+///     buffer::Terminal,
+///     input::{Event, KeyCode},
+///     visual::TextBlock,
+///     Command, EventLoop, Program,
+/// };
+///
+/// enum Msg {
+///     Increment,
+/// }
+///
+/// fn main() -> std::process::ExitCode {
+///     let mut program = Program::new(
+///         0,
+///         |count: &mut i32, message: Msg| match message {
+///             Msg::Increment => {
+///                 *count += 1;
+///
+///                 Command::none()
+///             }
+///         },
+///         |count: &i32| TextBlock::new(format!("Count: {count}")),
+///         Terminal::<io::Stdout>::from_stdout(),
+///     )
+///     .with_subscription(|_, event| match event {
+///         Event::Key(key) if key.code == KeyCode::Char('+') => Some(Msg::Increment),
+///         _ => None,
+///     });
+///
+///     EventLoop::default().run(&mut program)
+/// }
+/// ```
+pub struct Program<M, Msg, V, W>
+where
+    W: Write,
+    V: Visual,
+{
+    model: M,
+    update: Box<Update<M, Msg>>,
+    view: Box<dyn Fn(&M) -> V>,
+    /// Maps input events to messages before they are delivered to visuals.
+    subscription: Box<Subscription<M, Msg>>,
+    app: VisualApp<V, W>,
+    /// Tasks of commands, which are not yet taken by the event loop.
+    tasks: Vec<Task>,
+}
+
+impl<M, Msg, V, W> Program<M, Msg, V, W>
+where
+    M: 'static,
+    Msg: Any + Send,
+    V: Visual,
+    W: Write,
+{
+    pub fn new(
+        model: M,
+        update: impl FnMut(&mut M, Msg) -> Command<Msg> + 'static,
+        view: impl Fn(&M) -> V + 'static,
+        terminal: Terminal<W>,
+    ) -> Self {
+        let visual = view(&model);
+
+        Self {
+            model,
+            update: Box::new(update),
+            view: Box::new(view),
+            subscription: Box::new(|_, _| None),
+            app: VisualApp::new(visual, terminal),
+            tasks: vec![],
+        }
+    }
+
+    /// Sets the function which maps input events to messages, events which are mapped
+    /// are not delivered to visuals. Resize events are always delivered to visuals.
+    pub fn with_subscription(
+        mut self,
+        subscription: impl Fn(&M, &Event) -> Option<Msg> + 'static,
+    ) -> Self {
+        self.subscription = Box::new(subscription);
+        self
+    }
+
+    /// Configures the app which draws the view, e.g. its viewport.
+    pub fn with_app(mut self, configure: impl FnOnce(VisualApp<V, W>) -> VisualApp<V, W>) -> Self {
+        self.app = configure(self.app);
+        self
+    }
+
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    pub fn app(&self) -> &VisualApp<V, W> {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut VisualApp<V, W> {
+        &mut self.app
+    }
+
+    /// Updates the model with the message and the messages of returned commands,
+    /// then rebuilds the view. Returns the exit code if a command requested exit,
+    /// or the failure exit code if the view cannot be drawn.
+    pub fn dispatch(&mut self, message: Msg) -> Option<ExitCode> {
+        let mut messages = VecDeque::from([message]);
+        let mut exit_code = None;
+
+        while let Some(message) = messages.pop_front() {
+            for effect in (self.update)(&mut self.model, message).effects {
+                match effect {
+                    Effect::Message(message) => messages.push_back(message),
+                    Effect::Task(task) => self.tasks.push(task),
+                    Effect::Exit(code) => {
+                        exit_code.get_or_insert(code);
+                    }
+                }
+            }
+        }
+
+        let result = self.rebuild();
+
+        self.app.keep_error(result).or(exit_code)
+    }
+
+    /// Builds the view from the model and replaces the visual tree by it.
+    fn rebuild(&mut self) -> io::Result<()> {
+        let mut visual = (self.view)(&self.model);
+
+        visual.reconcile(self.app.visual());
+
+        self.app.set_visual(visual)
+    }
+}
+
+impl<M, Msg, V, W> EventHandler for Program<M, Msg, V, W>
+where
+    M: 'static,
+    Msg: Any + Send,
+    V: Visual,
+    W: Write,
+{
+    fn on_start(&mut self) -> io::Result<()> {
+        self.app.on_start()
+    }
+
+    fn on_event(&mut self, event: &Event) -> Option<ExitCode> {
+        if let Event::Resize(_, _) = event {
+            return self.app.on_event(event);
+        }
+
+        match (self.subscription)(&self.model, event) {
+            Some(message) => self.dispatch(message),
+            None => self.app.on_event(event),
+        }
+    }
+
+    fn on_tick(&mut self) -> Option<ExitCode> {
+        self.app.on_tick()
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.app.next_deadline()
+    }
+
+    fn on_deadline(&mut self) -> Option<ExitCode> {
+        self.app.on_deadline()
+    }
+
+    /// Messages of the program update the model, others are delivered to the visual tree.
+    fn on_message(&mut self, message: Message) -> Option<ExitCode> {
+        match message.downcast::<Msg>() {
+            Ok(message) => self.dispatch(*message),
+            Err(message) => self.app.on_message(message),
+        }
+    }
+
+    fn on_signal(&mut self, signal: Signal) -> Option<ExitCode> {
+        self.app.on_signal(signal)
+    }
+
+    fn take_tasks(&mut self) -> Vec<Task> {
+        let mut tasks = std::mem::take(&mut self.tasks);
+        tasks.extend(self.app.take_tasks());
+        tasks
+    }
+
+    /// Posted messages of the program update the model by [`EventHandler::on_message`].
+    fn take_messages(&mut self) -> Vec<Message> {
+        self.app.take_messages()
+    }
+
+    fn on_exit(&mut self) -> io::Result<()> {
+        self.app.on_exit()
+    }
+}
@@ -0,0 +1,151 @@
+use std::process::ExitCode;
+
+#[cfg(unix)]
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
+};
+
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGCONT, SIGHUP, SIGTERM},
+    flag,
+    iterator::{Handle, Signals},
+};
+
+/// Signals of the process which are delivered to [`crate::EventHandler::on_signal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    /// The process is continued after it was stopped, e.g. by Ctrl+Z.
+    Continue,
+    /// The process is asked to terminate, e.g. by `kill`.
+    Terminate,
+    /// The terminal of the process is closed.
+    Hangup,
+}
+
+impl Signal {
+    /// Returns the conventional exit code of a process terminated by the signal,
+    /// `None` if the signal does not terminate the process.
+    pub fn exit_code(&self) -> Option<ExitCode> {
+        match self {
+            Signal::Continue => None,
+            Signal::Terminate => Some(ExitCode::from(128 + 15)),
+            Signal::Hangup => Some(ExitCode::from(128 + 1)),
+        }
+    }
+}
+
+/// Listens to signals of the process on its own thread until it is dropped.
+#[cfg(unix)]
+pub(crate) struct SignalListener {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[cfg(unix)]
+impl SignalListener {
+    /// Registers the signals and passes them to the callback until it returns `false`
+    /// or the listener is stopped.
+    pub fn spawn(mut on_signal: impl FnMut(Signal) -> bool + Send + 'static) -> io::Result<Self> {
+        let mut signals = Signals::new([SIGCONT, SIGTERM, SIGHUP])?;
+        let handle = signals.handle();
+
+        DefaultActions::add_listener()?;
+
+        let thread = thread::spawn(move || {
+            for signal in signals.forever() {
+                let signal = match signal {
+                    SIGCONT => Signal::Continue,
+                    SIGTERM => Signal::Terminate,
+                    _ => Signal::Hangup,
+                };
+
+                if !on_signal(signal) {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            handle,
+            thread: Some(thread),
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SignalListener {
+    /// Stops listening, the signals terminate the process again
+    /// unless another listener is running.
+    fn drop(&mut self) {
+        self.handle.close();
+
+        if let Some(thread) = self.thread.take() {
+            // a panic of the thread is already reported by the panic hook
+            let _ = thread.join();
+        }
+
+        DefaultActions::remove_listener();
+    }
+}
+
+#[cfg(unix)]
+static DEFAULT_ACTIONS: Mutex<Option<DefaultActions>> = Mutex::new(None);
+
+/// Runs the default actions of the terminating signals while no listener is running,
+/// since signal-hook does not restore the original handlers when signals are unregistered.
+#[cfg(unix)]
+struct DefaultActions {
+    listeners: usize,
+    is_enabled: Arc<AtomicBool>,
+}
+
+#[cfg(unix)]
+impl DefaultActions {
+    /// Disables the default actions while the listener is running.
+    fn add_listener() -> io::Result<()> {
+        let mut default_actions = DEFAULT_ACTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let default_actions = match default_actions.as_mut() {
+            Some(default_actions) => default_actions,
+            None => {
+                let is_enabled = Arc::new(AtomicBool::new(false));
+
+                for signal in [SIGTERM, SIGHUP] {
+                    flag::register_conditional_default(signal, is_enabled.clone())?;
+                }
+
+                default_actions.insert(DefaultActions {
+                    listeners: 0,
+                    is_enabled,
+                })
+            }
+        };
+
+        default_actions.listeners += 1;
+        default_actions.is_enabled.store(false, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Enables the default actions when the last listener is stopped.
+    fn remove_listener() {
+        let mut default_actions = DEFAULT_ACTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(default_actions) = default_actions.as_mut() {
+            default_actions.listeners = default_actions.listeners.saturating_sub(1);
+            default_actions
+                .is_enabled
+                .store(default_actions.listeners == 0, Ordering::SeqCst);
+        }
+    }
+}
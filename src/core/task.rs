@@ -0,0 +1,45 @@
+use std::{
+    any::Any,
+    future::Future,
+    pin::{pin, Pin},
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use crate::Message;
+
+/// Future spawned through [`crate::visual::MutableContext::spawn`],
+/// its output is posted into the event loop as a message.
+pub type Task = Pin<Box<dyn Future<Output = Message> + Send>>;
+
+/// Creates [`Task`] which posts the output of the future as a message.
+pub fn task<F>(future: F) -> Task
+where
+    F: Future + Send + 'static,
+    F::Output: Any + Send,
+{
+    Box::pin(async move { Box::new(future.await) as Message })
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the future to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
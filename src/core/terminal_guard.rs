@@ -0,0 +1,116 @@
+use std::{
+    io::{self, Write},
+    panic::{self, PanicHookInfo},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+};
+
+use super::terminal_modes::TerminalModes;
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static;
+
+/// Keeps the terminal modes enabled until it is restored, and restores the terminal
+/// when the thread which created the guard panics, before the panic message is printed.
+pub(crate) struct TerminalGuard {
+    /// Modes which are still enabled, shared with the panic hook.
+    enabled_modes: Arc<Mutex<Option<TerminalModes>>>,
+    /// Panic hook which was replaced by the guard, shared with the panic hook.
+    previous_panic_hook: Arc<Mutex<Option<Box<PanicHook>>>>,
+    /// Address of the panic hook of the guard, to find out whether it is still installed.
+    panic_hook: usize,
+}
+
+impl TerminalGuard {
+    /// Enables the modes and installs the panic hook, which restores the terminal
+    /// through the panic writer, if there is one.
+    pub fn new(
+        modes: TerminalModes,
+        writer: &mut impl Write,
+        panic_writer: Option<fn() -> Box<dyn Write>>,
+    ) -> io::Result<Self> {
+        let enabled_modes = Arc::new(Mutex::new(Some(modes.enable(writer)?)));
+
+        let previous_panic_hook = Arc::new(Mutex::new(Some(panic::take_hook())));
+
+        let hook_modes = enabled_modes.clone();
+        let hook_previous = previous_panic_hook.clone();
+        let ui_thread = thread::current().id();
+
+        let panic_hook: Box<PanicHook> = Box::new(move |info| {
+            // panics of other threads do not stop the app
+            if thread::current().id() == ui_thread {
+                if let Some(panic_writer) = panic_writer {
+                    // errors are ignored, the panic is reported anyway
+                    let _ = restore(&hook_modes, &mut panic_writer());
+                }
+            }
+
+            let previous = hook_previous.lock().unwrap_or_else(PoisonError::into_inner);
+
+            if let Some(previous) = previous.as_ref() {
+                previous(info);
+            }
+        });
+        let panic_hook_address = hook_address(&*panic_hook);
+
+        panic::set_hook(panic_hook);
+
+        Ok(Self {
+            enabled_modes,
+            previous_panic_hook,
+            panic_hook: panic_hook_address,
+        })
+    }
+
+    /// Disables the enabled modes through the writer they were enabled on,
+    /// nothing happens if the terminal is already restored.
+    pub fn restore(&self, writer: &mut impl Write) -> io::Result<()> {
+        restore(&self.enabled_modes, writer)
+    }
+}
+
+/// Puts back the replaced panic hook, the terminal is restored by the owner of the writer.
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // the panic hook cannot be replaced while panicking
+        if thread::panicking() {
+            return;
+        }
+
+        let current = panic::take_hook();
+
+        // a hook which was installed after the guard is kept, it may still call the guard's hook
+        if hook_address(&*current) != self.panic_hook {
+            panic::set_hook(current);
+
+            return;
+        }
+
+        let previous = self
+            .previous_panic_hook
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        if let Some(previous) = previous {
+            panic::set_hook(previous);
+        }
+    }
+}
+
+fn hook_address(hook: &PanicHook) -> usize {
+    hook as *const PanicHook as *const () as usize
+}
+
+fn restore(
+    enabled_modes: &Mutex<Option<TerminalModes>>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    // the lock is poisoned only by a panic in `disable`, then the terminal is restored anyway
+    let modes = match enabled_modes.lock() {
+        Ok(mut modes) => modes.take(),
+        Err(_) => None,
+    };
+
+    modes.map_or(Ok(()), |modes| modes.disable(writer))
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    style::ResetColor,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Terminal reporting modes which are enabled while the app runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TerminalModes {
+    /// The app is drawn on the alternate screen, so the main screen is kept intact.
+    pub alternate_screen: bool,
+    /// Mouse buttons, wheel and motion reporting.
+    pub mouse_capture: bool,
+    /// Pasted text is reported as a single paste event instead of key presses.
+    pub bracketed_paste: bool,
+    /// Terminal window focus changes are reported.
+    pub focus_change: bool,
+    /// Keyboard enhancement flags, pushed only when the terminal supports them.
+    pub keyboard_enhancement: Option<KeyboardEnhancementFlags>,
+}
+
+impl Default for TerminalModes {
+    fn default() -> Self {
+        Self {
+            alternate_screen: true,
+            mouse_capture: true,
+            bracketed_paste: true,
+            focus_change: true,
+            keyboard_enhancement: None,
+        }
+    }
+}
+
+impl TerminalModes {
+    /// Enables raw mode and the modes and hides the cursor,
+    /// returns the modes which were actually enabled.
+    ///
+    /// If enabling fails, the modes are disabled again before the error is returned.
+    pub fn enable(&self, writer: &mut impl Write) -> io::Result<TerminalModes> {
+        self.try_enable(writer).inspect_err(|_| {
+            // keyboard enhancement flags are pushed last, so they are never pushed here
+            let modes = TerminalModes {
+                keyboard_enhancement: None,
+                ..*self
+            };
+
+            let _ = modes.disable(writer);
+        })
+    }
+
+    fn try_enable(&self, writer: &mut impl Write) -> io::Result<TerminalModes> {
+        terminal::enable_raw_mode()?;
+
+        if self.alternate_screen {
+            execute!(writer, EnterAlternateScreen)?;
+        }
+
+        execute!(writer, Hide)?;
+
+        if self.mouse_capture {
+            execute!(writer, EnableMouseCapture)?;
+        }
+
+        if self.bracketed_paste {
+            execute!(writer, EnableBracketedPaste)?;
+        }
+
+        if self.focus_change {
+            execute!(writer, EnableFocusChange)?;
+        }
+
+        // terminals without support of the protocol would print the sequence
+        let keyboard_enhancement = self
+            .keyboard_enhancement
+            .filter(|_| terminal::supports_keyboard_enhancement().unwrap_or(false));
+
+        if let Some(flags) = keyboard_enhancement {
+            execute!(writer, PushKeyboardEnhancementFlags(flags))?;
+        }
+
+        Ok(TerminalModes {
+            keyboard_enhancement,
+            ..*self
+        })
+    }
+
+    /// Disables the modes in the reverse order, shows the cursor and then disables raw mode.
+    ///
+    /// All modes are attempted to be disabled even if some of them fail,
+    /// the first error is returned.
+    pub fn disable(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut results = vec![];
+
+        if self.keyboard_enhancement.is_some() {
+            results.push(execute!(writer, PopKeyboardEnhancementFlags));
+        }
+
+        if self.focus_change {
+            results.push(execute!(writer, DisableFocusChange));
+        }
+
+        if self.bracketed_paste {
+            results.push(execute!(writer, DisableBracketedPaste));
+        }
+
+        if self.mouse_capture {
+            results.push(execute!(writer, DisableMouseCapture));
+        }
+
+        results.push(execute!(writer, ResetColor, Show));
+
+        if self.alternate_screen {
+            results.push(execute!(writer, LeaveAlternateScreen));
+        }
+
+        results.push(terminal::disable_raw_mode());
+
+        results.into_iter().collect()
+    }
+}
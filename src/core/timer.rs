@@ -0,0 +1,144 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Identifies a timer started through [`crate::visual::MutableContext::start_timer`].
+///
+/// Identifiers are unique within the process, so a visual can recognize its own timers
+/// when they are delivered to the whole visual tree.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct TimerId(u64);
+
+impl TimerId {
+    /// Creates a new unique identifier.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    /// Interval of the repeating timer, `None` for a one-shot timer.
+    interval: Option<Duration>,
+}
+
+/// Schedule of the started timers.
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    /// Starts the timer, restarting it if it is already started.
+    pub fn start(&mut self, id: TimerId, delay: Duration, repeat: bool) {
+        self.stop(id);
+
+        self.timers.push(Timer {
+            id,
+            deadline: Instant::now() + delay,
+            interval: repeat.then_some(delay),
+        });
+    }
+
+    pub fn stop(&mut self, id: TimerId) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// Returns the earliest deadline of the started timers.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    /// Returns timers which are due by now in the order of their deadlines,
+    /// one-shot timers are removed and repeating ones are rescheduled.
+    pub fn take_due(&mut self, now: Instant) -> Vec<TimerId> {
+        let mut due: Vec<(Instant, TimerId)> = vec![];
+
+        self.timers.retain_mut(|timer| {
+            if timer.deadline > now {
+                return true;
+            }
+
+            due.push((timer.deadline, timer.id));
+
+            match timer.interval {
+                Some(interval) => {
+                    // skip missed intervals instead of firing them all at once
+                    while timer.deadline <= now {
+                        timer.deadline += interval.max(Duration::from_millis(1));
+                    }
+
+                    true
+                }
+                None => false,
+            }
+        });
+
+        due.sort_by_key(|(deadline, _)| *deadline);
+
+        due.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn removes_due_one_shot_timer() {
+        let mut timers = Timers::default();
+        let id = TimerId::new();
+
+        timers.start(id, DELAY, false);
+        let deadline = timers.next_deadline().unwrap();
+
+        assert!(timers
+            .take_due(deadline - Duration::from_millis(1))
+            .is_empty());
+        assert_eq!(timers.take_due(deadline), [id]);
+        assert_eq!(timers.next_deadline(), None);
+    }
+
+    #[test]
+    fn reschedules_repeating_timer_skipping_missed_intervals() {
+        let mut timers = Timers::default();
+        let id = TimerId::new();
+
+        timers.start(id, DELAY, true);
+        let deadline = timers.next_deadline().unwrap();
+
+        assert_eq!(timers.take_due(deadline + DELAY * 2 + DELAY / 2), [id]);
+        assert_eq!(timers.next_deadline(), Some(deadline + DELAY * 3));
+    }
+
+    #[test]
+    fn returns_due_timers_in_order_of_deadlines() {
+        let mut timers = Timers::default();
+        let (late, early) = (TimerId::new(), TimerId::new());
+
+        timers.start(late, DELAY * 2, false);
+        timers.start(early, DELAY, false);
+
+        assert_eq!(timers.take_due(Instant::now() + DELAY * 3), [early, late]);
+    }
+
+    #[test]
+    fn restarts_started_timer() {
+        let mut timers = Timers::default();
+        let id = TimerId::new();
+
+        timers.start(id, DELAY, true);
+        timers.start(id, DELAY * 10, false);
+        let deadline = timers.next_deadline().unwrap();
+
+        assert_eq!(timers.take_due(deadline), [id]);
+        assert!(timers.take_due(deadline + DELAY * 10).is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+/// Area of the terminal where [`crate::VisualApp`] draws the visual.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Viewport {
+    /// The whole alternate screen, so the main screen and its scrollback stay intact.
+    #[default]
+    Fullscreen,
+    /// The given number of lines reserved below the cursor on the main screen,
+    /// the output above them stays in the terminal history.
+    Inline(u16),
+}
@@ -1,8 +1,14 @@
 mod event_args;
+mod focus;
+mod gesture;
 mod input_handler;
+mod keymap;
 
 pub use event_args::*;
+pub use focus::*;
+pub use gesture::*;
 pub use input_handler::*;
+pub use keymap::*;
 pub use {
     crossterm::event::poll, crossterm::event::read, crossterm::event::Event,
     crossterm::event::KeyCode, crossterm::event::KeyEvent, crossterm::event::KeyEventKind,
@@ -1,5 +1,6 @@
 use crate::{
     input::{KeyCode, KeyEvent, KeyEventState, KeyModifiers, MouseButton, MouseEvent},
+    layout::Offset,
     Position,
 };
 
@@ -34,7 +35,8 @@ impl KeyEventArgs {
 }
 
 pub struct MouseEventArgs {
-    /// Position of the cell that the event occurred on.
+    /// Position of the cell that the event occurred on,
+    /// relative to the visual which handles the event.
     pub position: Position,
     /// The key modifiers active when the event occurred.
     pub modifiers: KeyModifiers,
@@ -54,10 +56,16 @@ impl MouseEventArgs {
             modifiers: event.modifiers,
         }
     }
+
+    /// Returns the same event with the position relative to the given origin.
+    pub fn relative_to(&self, origin: Position) -> Self {
+        Self::new(self.position.relative_to(origin), self.modifiers)
+    }
 }
 
 pub struct MouseWheelEventArgs {
-    /// Position of the cell that the event occurred on.
+    /// Position of the cell that the event occurred on,
+    /// relative to the visual which handles the event.
     pub position: Position,
     /// The key modifiers active when the event occurred.
     pub modifiers: KeyModifiers,
@@ -83,10 +91,21 @@ impl MouseWheelEventArgs {
             is_vertical,
         }
     }
+
+    /// Returns the same event with the position relative to the given origin.
+    pub fn relative_to(&self, origin: Position) -> Self {
+        Self::new(
+            self.position.relative_to(origin),
+            self.modifiers,
+            self.delta,
+            self.is_vertical,
+        )
+    }
 }
 
 pub struct MouseButtonEventArgs {
-    /// Position of the cell that the event occurred on.
+    /// Position of the cell that the event occurred on,
+    /// relative to the visual which handles the event.
     pub position: Position,
     /// The key modifiers active when the event occurred.
     pub modifiers: KeyModifiers,
@@ -110,6 +129,95 @@ impl MouseButtonEventArgs {
             button,
         }
     }
+
+    /// Returns the same event with the position relative to the given origin.
+    pub fn relative_to(&self, origin: Position) -> Self {
+        Self::new(
+            self.position.relative_to(origin),
+            self.modifiers,
+            self.button,
+        )
+    }
+}
+
+pub struct ClickEventArgs {
+    /// Position of the cell that the button was released on,
+    /// relative to the visual which handles the event.
+    pub position: Position,
+    /// The key modifiers active when the event occurred.
+    pub modifiers: KeyModifiers,
+    /// The mouse button which was clicked.
+    pub button: MouseButton,
+    /// Number of consecutive clicks: 1 for a click, 2 for a double-click and so on.
+    pub click_count: u32,
+}
+
+impl ClickEventArgs {
+    pub fn new(
+        position: Position,
+        modifiers: KeyModifiers,
+        button: MouseButton,
+        click_count: u32,
+    ) -> Self {
+        Self {
+            position,
+            modifiers,
+            button,
+            click_count,
+        }
+    }
+
+    /// Returns the same event with the position relative to the given origin.
+    pub fn relative_to(&self, origin: Position) -> Self {
+        Self::new(
+            self.position.relative_to(origin),
+            self.modifiers,
+            self.button,
+            self.click_count,
+        )
+    }
+}
+
+pub struct DragEventArgs {
+    /// Position of the pointer, relative to the visual which handles the event.
+    pub position: Position,
+    /// Position where the button was pressed, relative to the visual which handles the event.
+    pub start_position: Position,
+    /// Movement of the pointer since the previous event of the drag.
+    pub delta: Offset,
+    /// The key modifiers active when the event occurred.
+    pub modifiers: KeyModifiers,
+    /// The mouse button which is pressed during the drag.
+    pub button: MouseButton,
+}
+
+impl DragEventArgs {
+    pub fn new(
+        position: Position,
+        start_position: Position,
+        delta: Offset,
+        modifiers: KeyModifiers,
+        button: MouseButton,
+    ) -> Self {
+        Self {
+            position,
+            start_position,
+            delta,
+            modifiers,
+            button,
+        }
+    }
+
+    /// Returns the same event with positions relative to the given origin.
+    pub fn relative_to(&self, origin: Position) -> Self {
+        Self::new(
+            self.position.relative_to(origin),
+            self.start_position.relative_to(origin),
+            self.delta,
+            self.modifiers,
+            self.button,
+        )
+    }
 }
 
 pub struct PasteEventArgs<'a> {
@@ -0,0 +1,39 @@
+use crate::input::{KeyCode, KeyEventArgs, KeyModifiers};
+
+/// Direction in which keyboard focus moves between focusable visuals.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FocusDirection {
+    /// Towards the next visual in the tree order, usually by Tab.
+    Next,
+    /// Towards the previous visual in the tree order, usually by Shift+Tab.
+    Previous,
+}
+
+impl FocusDirection {
+    /// Returns direction of the focus change requested by the key, if any.
+    pub fn from_key(args: &KeyEventArgs) -> Option<Self> {
+        match args.code {
+            KeyCode::BackTab => Some(FocusDirection::Previous),
+            KeyCode::Tab if args.modifiers.contains(KeyModifiers::SHIFT) => {
+                Some(FocusDirection::Previous)
+            }
+            KeyCode::Tab if args.modifiers.is_empty() => Some(FocusDirection::Next),
+            _ => None,
+        }
+    }
+
+    /// Returns indices of the given amount of items in the order of the direction,
+    /// starting after the given index or from the edge if it is `None`.
+    pub fn indices_after(
+        self,
+        index: Option<usize>,
+        count: usize,
+    ) -> Box<dyn Iterator<Item = usize>> {
+        match (self, index) {
+            (FocusDirection::Next, None) => Box::new(0..count),
+            (FocusDirection::Next, Some(index)) => Box::new(index + 1..count),
+            (FocusDirection::Previous, None) => Box::new((0..count).rev()),
+            (FocusDirection::Previous, Some(index)) => Box::new((0..index.min(count)).rev()),
+        }
+    }
+}
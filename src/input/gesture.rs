@@ -0,0 +1,248 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    input::{ClickEventArgs, DragEventArgs, MouseButton, MouseEvent, MouseEventKind},
+    layout::Offset,
+    Position,
+};
+
+/// Higher-level mouse event recognized from a sequence of raw mouse events.
+pub enum Gesture {
+    Click(ClickEventArgs),
+    DragStart(DragEventArgs),
+    Drag(DragEventArgs),
+    DragEnd(DragEventArgs),
+}
+
+/// Recognizes clicks and drags from raw mouse events.
+///
+/// A click is a press and a release of a button without dragging, containers deliver it only
+/// to the visual which received both. Clicks of the same button on the same cell within
+/// the click interval are counted as double-, triple- and further clicks.
+pub struct GestureRecognizer {
+    click_interval: Duration,
+    press: Option<Press>,
+    last_click: Option<LastClick>,
+}
+
+/// State of the pressed button.
+struct Press {
+    button: MouseButton,
+    start: Position,
+    /// Position of the previous event of the drag.
+    last: Position,
+    is_dragging: bool,
+}
+
+struct LastClick {
+    button: MouseButton,
+    position: Position,
+    time: Instant,
+    count: u32,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    pub const DEFAULT_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new() -> Self {
+        Self {
+            click_interval: Self::DEFAULT_CLICK_INTERVAL,
+            press: None,
+            last_click: None,
+        }
+    }
+
+    /// Sets maximum time between clicks which are counted as a multiple click.
+    pub fn with_click_interval(mut self, click_interval: Duration) -> Self {
+        self.click_interval = click_interval;
+        self
+    }
+
+    pub fn click_interval(&self) -> Duration {
+        self.click_interval
+    }
+
+    /// Processes the raw mouse event and returns the gesture which it completes, if any.
+    pub fn recognize(&mut self, event: &MouseEvent) -> Option<Gesture> {
+        let position = Position::new(event.column, event.row);
+
+        match event.kind {
+            MouseEventKind::Down(button) => {
+                self.press = Some(Press {
+                    button,
+                    start: position,
+                    last: position,
+                    is_dragging: false,
+                });
+
+                None
+            }
+            MouseEventKind::Drag(button) => {
+                let press = self.press.as_mut().filter(|press| press.button == button)?;
+                let args = DragEventArgs::new(
+                    position,
+                    press.start,
+                    delta(press.last, position),
+                    event.modifiers,
+                    button,
+                );
+
+                press.last = position;
+
+                if press.is_dragging {
+                    Some(Gesture::Drag(args))
+                } else {
+                    press.is_dragging = true;
+
+                    Some(Gesture::DragStart(args))
+                }
+            }
+            MouseEventKind::Up(button) => {
+                let press = self.press.take().filter(|press| press.button == button)?;
+
+                if press.is_dragging {
+                    return Some(Gesture::DragEnd(DragEventArgs::new(
+                        position,
+                        press.start,
+                        delta(press.last, position),
+                        event.modifiers,
+                        button,
+                    )));
+                }
+
+                let now = Instant::now();
+                let count = match &self.last_click {
+                    Some(last)
+                        if last.button == button
+                            && last.position == position
+                            && now.duration_since(last.time) <= self.click_interval =>
+                    {
+                        last.count + 1
+                    }
+                    _ => 1,
+                };
+
+                self.last_click = Some(LastClick {
+                    button,
+                    position,
+                    time: now,
+                    count,
+                });
+
+                Some(Gesture::Click(ClickEventArgs::new(
+                    position,
+                    event.modifiers,
+                    button,
+                    count,
+                )))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns movement from one position to another.
+fn delta(from: Position, to: Position) -> Offset {
+    Offset {
+        x: i32::from(to.x) - i32::from(from.x),
+        y: i32::from(to.y) - i32::from(from.y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::input::KeyModifiers;
+
+    fn event(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn click(recognizer: &mut GestureRecognizer, column: u16, row: u16) -> Option<u32> {
+        recognizer.recognize(&event(MouseEventKind::Down(MouseButton::Left), column, row));
+
+        match recognizer.recognize(&event(MouseEventKind::Up(MouseButton::Left), column, row)) {
+            Some(Gesture::Click(args)) => Some(args.click_count),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn counts_clicks_on_the_same_cell() {
+        let mut recognizer = GestureRecognizer::new();
+
+        assert_eq!(click(&mut recognizer, 2, 3), Some(1));
+        assert_eq!(click(&mut recognizer, 2, 3), Some(2));
+        assert_eq!(click(&mut recognizer, 2, 3), Some(3));
+        assert_eq!(click(&mut recognizer, 4, 3), Some(1));
+    }
+
+    #[test]
+    fn resets_click_count_after_the_interval() {
+        let mut recognizer = GestureRecognizer::new().with_click_interval(Duration::from_millis(1));
+
+        assert_eq!(click(&mut recognizer, 0, 0), Some(1));
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(click(&mut recognizer, 0, 0), Some(1));
+    }
+
+    #[test]
+    fn recognizes_drag_instead_of_click() {
+        let mut recognizer = GestureRecognizer::new();
+        let left = MouseButton::Left;
+
+        assert!(recognizer
+            .recognize(&event(MouseEventKind::Down(left), 1, 1))
+            .is_none());
+
+        let Some(Gesture::DragStart(start)) =
+            recognizer.recognize(&event(MouseEventKind::Drag(left), 3, 2))
+        else {
+            panic!("drag start expected");
+        };
+        assert_eq!(start.start_position, Position::new(1, 1));
+        assert_eq!((start.delta.x, start.delta.y), (2, 1));
+
+        let Some(Gesture::Drag(drag)) =
+            recognizer.recognize(&event(MouseEventKind::Drag(left), 2, 2))
+        else {
+            panic!("drag expected");
+        };
+        assert_eq!((drag.delta.x, drag.delta.y), (-1, 0));
+
+        let Some(Gesture::DragEnd(end)) =
+            recognizer.recognize(&event(MouseEventKind::Up(left), 2, 4))
+        else {
+            panic!("drag end expected");
+        };
+        assert_eq!(end.position, Position::new(2, 4));
+        assert_eq!((end.delta.x, end.delta.y), (0, 2));
+    }
+
+    #[test]
+    fn ignores_events_of_another_button() {
+        let mut recognizer = GestureRecognizer::new();
+
+        recognizer.recognize(&event(MouseEventKind::Down(MouseButton::Left), 0, 0));
+
+        assert!(recognizer
+            .recognize(&event(MouseEventKind::Drag(MouseButton::Right), 1, 0))
+            .is_none());
+        assert!(recognizer
+            .recognize(&event(MouseEventKind::Up(MouseButton::Right), 1, 0))
+            .is_none());
+    }
+}
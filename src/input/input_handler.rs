@@ -1,10 +1,14 @@
 #![allow(unused)]
 
+use std::any::Any;
+
 use crate::{
     input::{
-        KeyEventArgs, MouseButtonEventArgs, MouseEventArgs, MouseWheelEventArgs, PasteEventArgs,
+        ClickEventArgs, DragEventArgs, FocusDirection, KeyEventArgs, MouseButtonEventArgs,
+        MouseEventArgs, MouseWheelEventArgs, PasteEventArgs,
     },
     visual::MutableContext,
+    Size, TimerId,
 };
 
 pub struct EmptyVisualLeafInput;
@@ -31,6 +35,21 @@ pub trait VisualLeafInput {
     // These methods are not routed, because... i don't know.
     fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {}
     fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {}
+    fn on_mouse_enter(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {}
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {}
+
+    /// Called when the terminal is resized, after child visuals.
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {}
+
+    /// Called when a timer is due and no child visual has handled it.
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
+
+    /// Called when a message is posted into the event loop and no child visual has handled it.
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
 
     fn tunnel_key_press(
         &mut self,
@@ -123,6 +142,70 @@ pub trait VisualLeafInput {
     ) -> bool {
         false
     }
+
+    fn tunnel_click(
+        &mut self,
+        args: &ClickEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn bubble_click(
+        &mut self,
+        args: &ClickEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn tunnel_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn bubble_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn tunnel_drag(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn bubble_drag(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn tunnel_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn bubble_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
 }
 
 /// Provides handlers for common events.
@@ -131,11 +214,79 @@ pub trait VisualInput {
         false
     }
 
-    // These methods called even for not-focused visuals
+    // These methods are called when the visual or one of its descendants gets or loses focus.
     fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {}
 
     fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {}
 
+    /// Called when the pointer moves over the visual.
+    fn on_mouse_enter(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {}
+
+    /// Called when the pointer moves out of the visual.
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {}
+
+    /// Called on every tick of the event loop, even for not-focused visuals.
+    fn on_tick(&mut self, visual_context: &mut dyn MutableContext) {}
+
+    /// Called for all visuals when the terminal is resized, the size is the new size
+    /// of the area where the app draws.
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {}
+
+    /// Called when a timer is due, even for not-focused visuals. The timer is delivered
+    /// to visuals in the tree order until one of them returns `true`.
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
+
+    /// Called when a message is posted into the event loop by [`crate::MessageSender`],
+    /// even for not-focused visuals. The message is delivered to visuals in the tree order
+    /// until one of them returns `true`, visuals usually downcast it to their message type.
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
+
+    /// Returns whether the visual itself can get keyboard focus.
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Gives focus to the first focusable visual in the given direction, which is
+    /// the visual itself or one of its descendants. Returns `false` if there is none.
+    ///
+    /// Called only for visuals which are not focused.
+    fn focus_first(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if self.is_focusable() {
+            self.on_got_focus(visual_context);
+        }
+
+        self.is_focusable()
+    }
+
+    /// Moves focus to the next focusable visual in the given direction. Returns `false`
+    /// if there are no more such visuals, in this case the visual loses focus.
+    ///
+    /// Called only for focused visuals.
+    fn focus_next(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.clear_focus(visual_context);
+
+        false
+    }
+
+    /// Removes focus from the visual and its descendants.
+    ///
+    /// Called only for focused visuals.
+    fn clear_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        self.on_lost_focus(visual_context);
+    }
+
     fn on_key_press(
         &mut self,
         args: &KeyEventArgs,
@@ -183,4 +334,31 @@ pub trait VisualInput {
     ) -> bool {
         false
     }
+
+    /// Called when a button is pressed and released over the visual without moving the pointer.
+    fn on_click(&mut self, args: &ClickEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
+
+    /// Called when the pointer starts moving with a pressed button,
+    /// for the visual which received the press.
+    fn on_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
+
+    fn on_drag(&mut self, args: &DragEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        false
+    }
+
+    fn on_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        false
+    }
 }
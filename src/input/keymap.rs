@@ -0,0 +1,634 @@
+use std::{
+    error::Error,
+    fmt,
+    rc::Rc,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input::{KeyCode, KeyEventArgs, KeyModifiers, VisualLeafInput},
+    visual::MutableContext,
+};
+
+/// Key pressed together with modifiers, e.g. `Ctrl+K`.
+///
+/// Shifted characters are stored as the character itself without the `Shift` modifier,
+/// so `G` and `Shift+g` are the same combination, and `Shift+Tab` is stored as `BackTab`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct KeyCombination {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyCombination {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let (code, modifiers) = match code {
+            KeyCode::Char(char) if modifiers.contains(KeyModifiers::SHIFT) => (
+                KeyCode::Char(char.to_ascii_uppercase()),
+                modifiers - KeyModifiers::SHIFT,
+            ),
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+            }
+            KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+            _ => (code, modifiers),
+        };
+
+        Self { code, modifiers }
+    }
+
+    pub fn from_args(args: &KeyEventArgs) -> Self {
+        Self::new(args.code, args.modifiers)
+    }
+}
+
+impl FromStr for KeyCombination {
+    type Err = KeymapError;
+
+    /// Parses combinations like `g`, `Enter`, `Ctrl+K` or `Ctrl+Shift+F5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| KeymapError::InvalidKeys {
+            keys: s.to_owned(),
+            reason,
+        };
+
+        // the plus key itself is written as `+` or `Ctrl++`
+        let (modifier_names, key_name) = if s == "+" {
+            ("", "+")
+        } else if let Some(modifier_names) = s.strip_suffix("++") {
+            (modifier_names, "+")
+        } else {
+            match s.rfind('+') {
+                Some(index) => (&s[..index], &s[index + 1..]),
+                None => ("", s),
+            }
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" | "cmd" | "win" => KeyModifiers::SUPER,
+                "hyper" => KeyModifiers::HYPER,
+                "meta" => KeyModifiers::META,
+                _ => return Err(invalid(format!("unknown modifier `{name}`"))),
+            };
+        }
+
+        let mut chars = key_name.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => return Err(invalid("missing key".to_owned())),
+            // `Ctrl+K` is reported by terminals as `Ctrl+k`
+            (Some(char), None)
+                if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    && !modifiers.contains(KeyModifiers::SHIFT) =>
+            {
+                KeyCode::Char(char.to_ascii_lowercase())
+            }
+            (Some(char), None) => KeyCode::Char(char),
+            _ => key_code(key_name).ok_or_else(|| invalid(format!("unknown key `{key_name}`")))?,
+        };
+
+        Ok(KeyCombination::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyCombination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::SUPER, "Super"),
+            (KeyModifiers::HYPER, "Hyper"),
+            (KeyModifiers::META, "Meta"),
+            (KeyModifiers::SHIFT, "Shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(char) if self.modifiers.is_empty() => write!(f, "{char}"),
+            // letters with modifiers are written in upper case, so the shifted ones need `Shift`
+            KeyCode::Char(char) if char.is_ascii_uppercase() => write!(f, "Shift+{char}"),
+            KeyCode::Char(char) => write!(f, "{}", char.to_ascii_uppercase()),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Insert => write!(f, "Insert"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Returns code of the key with the given name, names are case-insensitive.
+fn key_code(name: &str) -> Option<KeyCode> {
+    let code = match name.to_ascii_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "space" => KeyCode::Char(' '),
+        "plus" => KeyCode::Char('+'),
+        name => match name.strip_prefix('f').map(str::parse) {
+            Some(Ok(number @ 1..=24)) => KeyCode::F(number),
+            _ => return None,
+        },
+    };
+
+    Some(code)
+}
+
+/// Key combinations which are pressed one after another, e.g. `g g` or `Ctrl+K Ctrl+C`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct KeySequence(Vec<KeyCombination>);
+
+impl KeySequence {
+    /// Creates sequence of the given combinations, `None` if there are no combinations.
+    pub fn new(keys: Vec<KeyCombination>) -> Option<Self> {
+        (!keys.is_empty()).then_some(Self(keys))
+    }
+
+    pub fn keys(&self) -> &[KeyCombination] {
+        &self.0
+    }
+
+    pub fn first(&self) -> KeyCombination {
+        self.0[0]
+    }
+
+    /// Returns value indicating whether one of the sequences starts with the other.
+    fn overlaps(&self, other: &KeySequence) -> bool {
+        self.0.starts_with(&other.0) || other.0.starts_with(&self.0)
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = KeymapError;
+
+    /// Parses combinations separated by whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(KeyCombination::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        KeySequence::new(keys).ok_or_else(|| KeymapError::InvalidKeys {
+            keys: s.to_owned(),
+            reason: "empty key sequence".to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, key) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{key}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Key sequence bound to a named command.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Binding {
+    pub keys: KeySequence,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum KeymapError {
+    /// The key sequence cannot be parsed.
+    InvalidKeys { keys: String, reason: String },
+    /// The key sequence is already bound, or it starts a bound sequence or vice versa,
+    /// so one of the bindings could never be completed.
+    Conflict {
+        keys: KeySequence,
+        existing: Binding,
+    },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::InvalidKeys { keys, reason } => {
+                write!(f, "invalid key sequence `{keys}`: {reason}")
+            }
+            KeymapError::Conflict { keys, existing } => write!(
+                f,
+                "`{keys}` conflicts with `{}` bound to `{}`",
+                existing.keys, existing.command
+            ),
+        }
+    }
+}
+
+impl Error for KeymapError {}
+
+/// Result of looking up the pressed keys in the [`Keymap`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeymapMatch<'a> {
+    /// No binding starts with the keys.
+    None,
+    /// The keys start a chord, more keys are expected.
+    Pending,
+    /// The keys are bound to the command.
+    Command(&'a str),
+}
+
+/// Maps key sequences to named commands.
+///
+/// A keymap is scoped to a visual by [`KeymapInput`]. Keys which are not bound by
+/// the keymap of a visual bubble to its ancestors, so bindings of ancestors are inherited
+/// and descendants override them.
+///
+/// # Example
+///
+/// ```
+/// use termrs::input::{Keymap, KeymapError};
+///
+/// let mut keymap = Keymap::new();
+///
+/// keymap.bind("g g", "top").unwrap();
+/// keymap.bind("Ctrl+K Ctrl+C", "comment").unwrap();
+///
+/// assert!(matches!(keymap.bind("g", "go"), Err(KeymapError::Conflict { .. })));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the key sequence and binds it to the command.
+    pub fn bind(&mut self, keys: &str, command: impl Into<String>) -> Result<(), KeymapError> {
+        self.bind_sequence(keys.parse()?, command)
+    }
+
+    pub fn bind_sequence(
+        &mut self,
+        keys: KeySequence,
+        command: impl Into<String>,
+    ) -> Result<(), KeymapError> {
+        if let Some(existing) = self
+            .bindings
+            .iter()
+            .find(|binding| binding.keys.overlaps(&keys))
+        {
+            return Err(KeymapError::Conflict {
+                keys,
+                existing: existing.clone(),
+            });
+        }
+
+        self.bindings.push(Binding {
+            keys,
+            command: command.into(),
+        });
+
+        Ok(())
+    }
+
+    /// Binds the key sequence to the command, removing bindings which conflict with it.
+    pub fn rebind(&mut self, keys: KeySequence, command: impl Into<String>) {
+        self.bindings
+            .retain(|binding| !binding.keys.overlaps(&keys));
+
+        self.bindings.push(Binding {
+            keys,
+            command: command.into(),
+        });
+    }
+
+    /// Removes binding of the key sequence and returns it.
+    pub fn unbind(&mut self, keys: &KeySequence) -> Option<Binding> {
+        let index = self
+            .bindings
+            .iter()
+            .position(|binding| &binding.keys == keys)?;
+
+        Some(self.bindings.remove(index))
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Returns key sequences bound to the command.
+    pub fn keys_of<'a>(&'a self, command: &'a str) -> impl Iterator<Item = &'a KeySequence> {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.command == command)
+            .map(|binding| &binding.keys)
+    }
+
+    pub fn lookup(&self, keys: &[KeyCombination]) -> KeymapMatch<'_> {
+        let mut result = KeymapMatch::None;
+
+        for binding in self.bindings.iter() {
+            if binding.keys.keys() == keys {
+                return KeymapMatch::Command(&binding.command);
+            }
+
+            if binding.keys.keys().starts_with(keys) {
+                result = KeymapMatch::Pending;
+            }
+        }
+
+        result
+    }
+
+    /// Returns bindings which can be completed in the given scopes,
+    /// ordered from the innermost scope to the outermost one.
+    ///
+    /// The binding of an outer scope is shadowed when an inner scope
+    /// has a binding starting with the same key.
+    pub fn active_bindings<'a>(scopes: &[&'a Keymap]) -> Vec<&'a Binding> {
+        let mut active: Vec<&Binding> = vec![];
+
+        for keymap in scopes {
+            let shadowing: Vec<KeyCombination> =
+                active.iter().map(|binding| binding.keys.first()).collect();
+
+            active.extend(
+                keymap
+                    .bindings
+                    .iter()
+                    .filter(|binding| !shadowing.contains(&binding.keys.first())),
+            );
+        }
+
+        active
+    }
+}
+
+/// Leaf input of a visual which translates key presses to commands of the [`Keymap`]
+/// and passes them to the handler.
+///
+/// The handler returns value indicating whether the command was handled,
+/// keys of unhandled commands bubble to ancestors.
+pub struct KeymapInput<H>
+where
+    H: FnMut(&str, &mut dyn MutableContext) -> bool,
+{
+    keymap: Rc<Keymap>,
+    handler: H,
+    /// Keys of the chord which is not completed yet.
+    pending: Vec<KeyCombination>,
+    /// Time of the last key of the pending chord.
+    pending_since: Option<Instant>,
+    chord_timeout: Duration,
+    /// Handle keys before the descendants or after them.
+    tunnel: bool,
+}
+
+impl<H> KeymapInput<H>
+where
+    H: FnMut(&str, &mut dyn MutableContext) -> bool,
+{
+    pub const DEFAULT_CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+    pub fn new(keymap: Rc<Keymap>, handler: H) -> Self {
+        Self {
+            keymap,
+            handler,
+            pending: vec![],
+            pending_since: None,
+            chord_timeout: Self::DEFAULT_CHORD_TIMEOUT,
+            tunnel: false,
+        }
+    }
+
+    /// Sets maximum time between keys of a chord, the pending chord is discarded after it.
+    pub fn with_chord_timeout(mut self, chord_timeout: Duration) -> Self {
+        self.chord_timeout = chord_timeout;
+        self
+    }
+
+    /// Sets whether keys are handled before the descendants of the visual,
+    /// so the bindings cannot be overridden by them.
+    pub fn with_tunnel(mut self, tunnel: bool) -> Self {
+        self.tunnel = tunnel;
+        self
+    }
+
+    pub fn keymap(&self) -> &Rc<Keymap> {
+        &self.keymap
+    }
+
+    /// Returns keys of the chord which is not completed yet, e.g. to show them in a status bar.
+    pub fn pending(&self) -> &[KeyCombination] {
+        &self.pending
+    }
+
+    fn process_key(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if self
+            .pending_since
+            .is_some_and(|since| since.elapsed() > self.chord_timeout)
+        {
+            self.pending.clear();
+        }
+
+        let continues_chord = !self.pending.is_empty();
+
+        self.pending.push(KeyCombination::from_args(args));
+
+        match self.keymap.lookup(&self.pending) {
+            KeymapMatch::Pending => {
+                self.pending_since = Some(Instant::now());
+
+                true
+            }
+            KeymapMatch::Command(command) => {
+                self.pending.clear();
+
+                (self.handler)(command, visual_context)
+            }
+            KeymapMatch::None => {
+                self.pending.clear();
+
+                // the key which breaks the chord can start a new one
+                continues_chord && self.process_key(args, visual_context)
+            }
+        }
+    }
+}
+
+impl<H> VisualLeafInput for KeymapInput<H>
+where
+    H: FnMut(&str, &mut dyn MutableContext) -> bool,
+{
+    fn tunnel_key_press(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.tunnel && self.process_key(args, visual_context)
+    }
+
+    fn bubble_key_press(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        !self.tunnel && self.process_key(args, visual_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, thread};
+
+    use super::*;
+    use crate::{input::KeyEventState, visual::RetainedMutableContext};
+
+    fn keymap_input(
+        commands: &Rc<RefCell<Vec<String>>>,
+    ) -> KeymapInput<impl FnMut(&str, &mut dyn MutableContext) -> bool> {
+        let mut keymap = Keymap::new();
+        keymap.bind("g g", "top").unwrap();
+        keymap.bind("d", "delete").unwrap();
+
+        let commands = commands.clone();
+
+        KeymapInput::new(Rc::new(keymap), move |command, _| {
+            commands.borrow_mut().push(command.to_owned());
+            true
+        })
+    }
+
+    fn press<H>(input: &mut KeymapInput<H>, char: char) -> bool
+    where
+        H: FnMut(&str, &mut dyn MutableContext) -> bool,
+    {
+        let args = KeyEventArgs::new(KeyCode::Char(char), KeyModifiers::NONE, KeyEventState::NONE);
+        let mut actions = vec![];
+
+        input.bubble_key_press(&args, &mut RetainedMutableContext::new(&mut actions))
+    }
+
+    #[test]
+    fn formats_parsed_combinations_back() {
+        for keys in [
+            "g",
+            "G",
+            "+",
+            "Enter",
+            "Ctrl+K",
+            "Ctrl++",
+            "Alt+Space",
+            "Shift+Tab",
+            "Ctrl+Shift+K",
+            "Ctrl+Shift+F5",
+            "Ctrl+K Ctrl+C",
+        ] {
+            assert_eq!(keys.parse::<KeySequence>().unwrap().to_string(), keys);
+        }
+    }
+
+    #[test]
+    fn normalizes_shifted_keys() {
+        let parse = |keys: &str| keys.parse::<KeyCombination>().unwrap();
+
+        assert_eq!(parse("Shift+g"), parse("G"));
+        assert_eq!(parse("Shift+Tab"), parse("BackTab"));
+        assert_eq!(parse("ctrl+k"), parse("Ctrl+K"));
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        for keys in ["", "Ctrl+", "Foo+x", "F25", "Ctrl+Nothing"] {
+            assert!(
+                matches!(
+                    keys.parse::<KeySequence>(),
+                    Err(KeymapError::InvalidKeys { .. })
+                ),
+                "{keys}"
+            );
+        }
+    }
+
+    #[test]
+    fn completes_pending_chord() {
+        let commands = Rc::default();
+        let mut input = keymap_input(&commands);
+
+        assert!(press(&mut input, 'g'));
+        assert_eq!(input.pending().len(), 1);
+        assert!(commands.borrow().is_empty());
+
+        assert!(press(&mut input, 'g'));
+        assert!(input.pending().is_empty());
+        assert_eq!(*commands.borrow(), ["top"]);
+    }
+
+    #[test]
+    fn dispatches_key_which_breaks_chord() {
+        let commands = Rc::default();
+        let mut input = keymap_input(&commands);
+
+        press(&mut input, 'g');
+
+        assert!(press(&mut input, 'd'));
+        assert!(input.pending().is_empty());
+        assert_eq!(*commands.borrow(), ["delete"]);
+
+        assert!(!press(&mut input, 'x'));
+    }
+
+    #[test]
+    fn discards_chord_after_timeout() {
+        let commands = Rc::default();
+        let mut input = keymap_input(&commands).with_chord_timeout(Duration::from_millis(1));
+
+        press(&mut input, 'g');
+        thread::sleep(Duration::from_millis(5));
+
+        assert!(press(&mut input, 'g'));
+        assert_eq!(input.pending().len(), 1);
+        assert!(commands.borrow().is_empty());
+    }
+}
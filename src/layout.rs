@@ -1,34 +1,35 @@
+mod alignment;
 mod margin;
 mod offset;
+mod orientation;
 
-pub use {margin::*, offset::*};
+pub use {alignment::*, margin::*, offset::*, orientation::*};
 
 use std::vec;
 
-use crate::{
-    buffer::{VirtualBuffer, WriteBuffer},
-    visual::Visual,
-    Position, Rect, Size,
-};
+use crate::{visual::Visual, Position, Rect, Size};
 
-/// Provides strategy for drawing and measuring collection of [`Visual`]
+/// Provides strategy for arranging and measuring collection of [`Visual`]
 pub trait Layout {
     // TODO: Take abstract iterator instead of concrete vector
-    /// Draws the children to the given buffer
-    fn draw(
-        &self,
-        children: &[Box<dyn Visual>],
-        buffer: &mut dyn WriteBuffer,
-        available_size: Size,
-    ) -> Box<dyn VisualArrangement>;
+    /// Arranges the children within the final size and returns their regions,
+    /// the children are rendered in them by the parent.
+    fn arrange(&self, children: &[Box<dyn Visual>], final_size: Size)
+        -> Box<dyn VisualArrangement>;
 
     /// Measures extent of the children
     fn measure(&self, children: &[Box<dyn Visual>], constraints: Size) -> Size;
 }
 
+/// Result of arranging children by a [`Layout`], which is used to render them
+/// and to route mouse events.
 pub trait VisualArrangement {
     fn size(&self) -> Size;
     fn size_visual(&self, index: usize) -> Size;
+    /// Returns region of the child with the given index in coordinates of the parent,
+    /// or `None` if the child was not arranged.
+    fn visual_rect(&self, index: usize) -> Option<Rect>;
+    /// Returns index of the child which is drawn at the given position.
     fn visual_hit(&self, position: Position) -> Option<usize>;
 }
 
@@ -36,35 +37,31 @@ pub trait VisualArrangement {
 pub struct VStackLayout;
 
 impl Layout for VStackLayout {
-    fn draw(
+    fn arrange(
         &self,
         children: &[Box<dyn Visual>],
-        buffer: &mut dyn WriteBuffer,
-        available_size: Size,
+        final_size: Size,
     ) -> Box<dyn VisualArrangement> {
         let mut rects: Vec<Rect> = vec![];
-        let mut draw_size = available_size;
+        let mut arrange_size = final_size;
         let mut max_width = 0;
         let mut y = 0;
 
         for child in children {
-            if draw_size.height == 0 {
+            if arrange_size.height == 0 {
                 break;
             }
 
-            let mut virtual_buffer =
-                VirtualBuffer::new(buffer, Rect::new(0, y, draw_size.width, draw_size.height));
-
-            let child_size = child.draw(&mut virtual_buffer, draw_size);
+            let child_size = child.arrange(arrange_size);
 
-            y += child_size.height;
-            draw_size.height -= child_size.height;
-            max_width += max_width.max(child_size.width);
             rects.push(Rect::new(0, y, child_size.width, child_size.height));
+            y += child_size.height;
+            arrange_size.height -= child_size.height;
+            max_width = max_width.max(child_size.width);
         }
 
         Box::new(RectArrangement {
-            size: Size::new(max_width, available_size.height - draw_size.height),
+            size: Size::new(max_width, final_size.height - arrange_size.height),
             rects,
         })
     }
@@ -89,11 +86,18 @@ impl Layout for VStackLayout {
     }
 }
 
-struct RectArrangement {
+/// Arrangement of children in the given regions.
+pub struct RectArrangement {
     size: Size,
     rects: Vec<Rect>,
 }
 
+impl RectArrangement {
+    pub fn new(size: Size, rects: Vec<Rect>) -> Self {
+        Self { size, rects }
+    }
+}
+
 impl VisualArrangement for RectArrangement {
     fn size(&self) -> Size {
         self.size
@@ -104,6 +108,11 @@ impl VisualArrangement for RectArrangement {
 
         rect.as_size()
     }
+
+    fn visual_rect(&self, index: usize) -> Option<Rect> {
+        self.rects.get(index).copied()
+    }
+
     fn visual_hit(&self, position: Position) -> Option<usize> {
         for (index, rect) in self.rects.iter().enumerate() {
             if rect.contains(position) {
@@ -0,0 +1,22 @@
+/// Horizontal placement of content inside a wider area.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    /// Returns offset of the content of the given width
+    /// inside the area of the given width.
+    pub fn offset(self, content_width: u16, available_width: u16) -> u16 {
+        let free = available_width.saturating_sub(content_width);
+
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => free / 2,
+            Alignment::Right => free,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+/// Direction in which content is laid out.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Orientation {
+    Horizontal,
+    #[default]
+    Vertical,
+}
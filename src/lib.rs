@@ -1,4 +1,7 @@
+pub mod animation;
 pub mod buffer;
+#[cfg(feature = "config")]
+pub mod config;
 mod core;
 pub mod input;
 pub mod layout;
@@ -1,13 +1,19 @@
 mod content_visual;
+mod identified;
 mod mutable_context;
+mod traversal;
 mod tree_visual;
 mod visuals;
 
 pub use content_visual::*;
+pub use identified::*;
 pub use mutable_context::*;
+pub use traversal::*;
 pub use tree_visual::*;
 pub use visuals::*;
 
+use std::any::Any;
+
 use crate::{
     buffer::WriteBuffer,
     input::{VisualInput, VisualLeafInput},
@@ -17,13 +23,76 @@ use crate::{
 pub trait Draw {
     /// Render itself to the given buffer
     /// and returns size of consumed region.
+    ///
+    /// Visuals with children implement it by [`Draw::arrange`] and [`Draw::render`].
     fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size;
 
     /// Measures size of the visual.
     fn measure(&self, constraints: Size) -> Size;
+
+    /// Positions descendants of the visual within the final size given by the parent
+    /// and returns size of the visual, which does not exceed the final size.
+    fn arrange(&self, final_size: Size) -> Size {
+        self.measure(final_size).clip(final_size)
+    }
+
+    /// Paints the visual in the size returned by the last arrange,
+    /// descendants are painted in the regions computed by it.
+    fn render(&self, buffer: &mut dyn WriteBuffer, size: Size) {
+        self.draw(buffer, size);
+    }
+
+    /// Returns whether the visual or one of its descendants runs an animation,
+    /// the app redraws it on every frame while it does.
+    fn is_animating(&self) -> bool {
+        false
+    }
+
+    /// Discards what the visual knows about its last draw, so the next draw computes
+    /// the invalid part again, e.g. [`Invalidation::Render`] after its area was cleared.
+    ///
+    /// Parents call it when the visual requests invalidation through [`MutableContext`].
+    fn invalidate(&self, _invalidation: Invalidation) {}
 }
 
-pub trait Visual: Draw + VisualInput {}
+pub trait Visual: Draw + VisualInput + AsAny {
+    /// Returns id which identifies the visual in the visual tree, see [`Identify::with_id`].
+    fn id(&self) -> Option<&VisualId> {
+        None
+    }
+
+    /// Calls the visitor for each child of the visual in their order,
+    /// lookups and traversal of the visual tree are based on it.
+    fn visit_children<'a>(&'a self, _visitor: &mut dyn FnMut(&'a dyn Visual)) {}
+
+    /// Calls the visitor for each child of the visual in their order for mutation.
+    fn visit_children_mut<'a>(&'a mut self, _visitor: &mut dyn FnMut(&'a mut dyn Visual)) {}
+
+    /// Takes state which is not described by the view, e.g. focus, scroll offset
+    /// or selection, from the visual which is replaced by this one after the view
+    /// is rebuilt, see [`crate::Program`]. Nothing is taken from visuals of other types.
+    fn reconcile(&mut self, _previous: &dyn Visual) {}
+}
+
+/// Converts visuals to [`Any`] for downcasting, it is implemented for all `'static` types.
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T> AsAny for T
+where
+    T: Any,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
 
 /// [`Visual`] which has no chlidren.
 pub trait VisualLeaf: Draw + VisualLeafInput {}
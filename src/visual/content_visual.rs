@@ -1,15 +1,25 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+};
+
 use crate::{
-    buffer::WriteBuffer,
+    buffer::{VirtualBuffer, WriteBuffer},
     input::{
-        KeyEventArgs, MouseButtonEventArgs, MouseEventArgs, MouseWheelEventArgs, PasteEventArgs,
-        VisualInput, VisualLeafInput,
+        ClickEventArgs, DragEventArgs, FocusDirection, KeyEventArgs, MouseButtonEventArgs,
+        MouseEventArgs, MouseWheelEventArgs, PasteEventArgs, VisualInput, VisualLeafInput,
     },
-    visual::{Draw, MutableContext, Visual},
-    Size,
+    layout::VisualArrangement,
+    visual::{ChildMutableContext, Draw, Invalidation, MutableContext, Visual, VisualId},
+    Position, Rect, Size, TimerId,
 };
 
 pub trait ContentLayout {
-    fn draw(&self, child: &dyn Visual, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size;
+    /// Arranges the child within the final size, the child has index 0 in the arrangement.
+    fn arrange(&self, child: &dyn Visual, final_size: Size) -> Box<dyn VisualArrangement>;
+
+    /// Paints the visual around the child, the child is rendered by the [`ContentVisual`].
+    fn render(&self, buffer: &mut dyn WriteBuffer, arrangement: &dyn VisualArrangement);
 
     fn measure(&self, child: &dyn Visual, constraints: Size) -> Size;
 }
@@ -18,8 +28,233 @@ pub trait ContentLayout {
 pub struct ContentVisual {
     child: Box<dyn Visual>,
     is_child_focused: bool,
+    is_child_hovered: bool,
+    is_child_captured: bool,
+    is_child_pressed: bool,
     layout: Box<dyn ContentLayout>,
     input_handler: Box<dyn VisualLeafInput>,
+    /// Arrangement of the child after the last arrange, which is used
+    /// to render it and for hit testing.
+    arrangement: RefCell<Option<Box<dyn VisualArrangement>>>,
+    /// Constraints and result of the last measure.
+    measure_cache: Cell<Option<(Size, Size)>>,
+    /// Final size of the last arrange, `None` if the child has to be arranged again.
+    arranged_size: Cell<Option<Size>>,
+    /// Whether the visual was painted and only the child has to be painted again.
+    is_rendered: Cell<bool>,
+}
+
+impl ContentVisual {
+    pub fn new(
+        child: Box<dyn Visual>,
+        layout: Box<dyn ContentLayout>,
+        input_handler: Box<dyn VisualLeafInput>,
+    ) -> Self {
+        Self {
+            child,
+            is_child_focused: false,
+            is_child_hovered: false,
+            is_child_captured: false,
+            is_child_pressed: false,
+            layout,
+            input_handler,
+            arrangement: RefCell::new(None),
+            measure_cache: Cell::new(None),
+            arranged_size: Cell::new(None),
+            is_rendered: Cell::new(false),
+        }
+    }
+
+    pub fn child(&self) -> &dyn Visual {
+        &*self.child
+    }
+
+    /// Finds the descendant with the given id and downcasts it to the given type.
+    pub fn find<T: Visual>(&self, id: impl Into<VisualId>) -> Option<&T> {
+        (self as &dyn Visual).find(id)
+    }
+
+    /// Finds the descendant with the given id and downcasts it to the given type for mutation,
+    /// it is measured and painted by the next draw.
+    pub fn find_mut<T: Visual>(&mut self, id: impl Into<VisualId>) -> Option<&mut T> {
+        (self as &mut dyn Visual).find_mut(id)
+    }
+
+    /// Returns region of the child after the last arrange in coordinates of the visual,
+    /// or `None` if the child was not arranged.
+    pub fn child_rect(&self) -> Option<Rect> {
+        self.arrangement
+            .borrow()
+            .as_ref()
+            .and_then(|arrangement| arrangement.visual_rect(0))
+    }
+
+    /// Calls the function for the child, if it is focused.
+    fn with_focused_child(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        if self.is_child_focused {
+            self.with_child(visual_context, f)
+        } else {
+            false
+        }
+    }
+
+    /// Returns origin of the child, if the child is drawn at the given position.
+    fn hit(&self, position: Position) -> Option<Position> {
+        let arrangement = self.arrangement.borrow();
+        let arrangement = arrangement.as_ref()?;
+        let index = arrangement.visual_hit(position)?;
+
+        Some(arrangement.visual_rect(index)?.as_position())
+    }
+
+    /// Returns origin of the child after the last draw.
+    fn origin(&self) -> Position {
+        self.arrangement
+            .borrow()
+            .as_ref()
+            .and_then(|arrangement| arrangement.visual_rect(0))
+            .map(|rect| rect.as_position())
+            .unwrap_or_default()
+    }
+
+    /// Returns origin of the child, if the child captured the mouse
+    /// or is drawn at the given position.
+    fn mouse_target(&self, position: Position) -> Option<Position> {
+        if self.is_child_captured {
+            Some(self.origin())
+        } else {
+            self.hit(position)
+        }
+    }
+
+    /// Calls the function for the child with its origin, if the child captured the mouse
+    /// or is drawn at the given position.
+    fn with_mouse_target(
+        &mut self,
+        position: Position,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        match self.mouse_target(position) {
+            Some(origin) => {
+                self.with_child(visual_context, |child, context| f(child, origin, context))
+            }
+            None => false,
+        }
+    }
+
+    /// Calls the function for the child with its origin, if the child received the press
+    /// and is the target of the click at the given position.
+    fn with_click_target(
+        &mut self,
+        position: Position,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        let is_child_pressed = std::mem::take(&mut self.is_child_pressed);
+
+        match self.mouse_target(position) {
+            Some(origin) if is_child_pressed => {
+                self.with_child(visual_context, |child, context| f(child, origin, context))
+            }
+            _ => false,
+        }
+    }
+
+    /// Calls the function for the child with its origin, if the child captured the mouse
+    /// or received the press which started the drag.
+    fn with_drag_target(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        if self.is_child_captured || self.is_child_pressed {
+            let origin = self.origin();
+
+            self.with_child(visual_context, |child, context| f(child, origin, context))
+        } else {
+            false
+        }
+    }
+
+    /// Sends leave and enter events when the pointer moves out of or over the child.
+    fn update_hovered(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        // the child which captured the mouse stays hovered until the capture is released
+        if self.is_child_captured {
+            return;
+        }
+
+        let hit = self.hit(args.position);
+
+        if hit.is_some() == self.is_child_hovered {
+            return;
+        }
+
+        self.is_child_hovered = hit.is_some();
+
+        match hit {
+            Some(origin) => self.with_child(visual_context, |child, context| {
+                child.on_mouse_enter(&args.relative_to(origin), context)
+            }),
+            None => self.with_child(visual_context, |child, context| {
+                child.on_mouse_leave(context)
+            }),
+        }
+    }
+
+    /// Calls the function for the child and processes focus request of the child.
+    fn with_child<R>(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, &mut dyn MutableContext) -> R,
+    ) -> R {
+        let mut context = ChildMutableContext::new(visual_context);
+        let result = f(&mut *self.child, &mut context);
+
+        let (focus_request, mouse_capture_request, invalidation) = (
+            context.focus_request(),
+            context.mouse_capture_request(),
+            context.invalidation(),
+        );
+
+        if let Some(invalidation) = invalidation.filter(|i| *i > Invalidation::Descendants) {
+            self.child.invalidate(invalidation);
+
+            match invalidation {
+                Invalidation::Measure => self.invalidate(Invalidation::Measure),
+                Invalidation::Arrange => self.invalidate(Invalidation::Arrange),
+                _ => {}
+            }
+        }
+
+        match focus_request {
+            Some(true) => {
+                // the visual and the child are notified by the parent
+                self.is_child_focused = true;
+                visual_context.set_focus(true);
+            }
+            Some(false) if self.is_child_focused => visual_context.set_focus(false),
+            _ => {}
+        }
+
+        match mouse_capture_request {
+            Some(true) => {
+                self.is_child_captured = true;
+                visual_context.set_mouse_capture(true);
+            }
+            Some(false) if self.is_child_captured => {
+                self.is_child_captured = false;
+                visual_context.set_mouse_capture(false);
+            }
+            _ => {}
+        }
+
+        result
+    }
 }
 
 impl VisualInput for ContentVisual {
@@ -27,8 +262,10 @@ impl VisualInput for ContentVisual {
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_paste(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_paste(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled = self.with_focused_child(visual_context, |child, context| {
+                child.on_paste(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -39,11 +276,99 @@ impl VisualInput for ContentVisual {
     }
 
     fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {
-        self.child.on_got_focus(visual_context)
+        self.input_handler.on_got_focus(visual_context);
+
+        if self.is_child_focused {
+            self.child.on_got_focus(visual_context);
+        }
     }
 
     fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {
-        self.child.on_lost_focus(visual_context)
+        self.input_handler.on_lost_focus(visual_context);
+
+        if self.is_child_focused {
+            self.child.on_lost_focus(visual_context);
+        }
+    }
+
+    fn on_mouse_enter(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        // the child is entered by the following mouse move
+        self.input_handler.on_mouse_enter(args, visual_context);
+    }
+
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {
+        self.input_handler.on_mouse_leave(visual_context);
+
+        if self.is_child_hovered {
+            self.is_child_hovered = false;
+            self.with_child(visual_context, |child, context| {
+                child.on_mouse_leave(context)
+            });
+        }
+    }
+
+    fn on_tick(&mut self, visual_context: &mut dyn MutableContext) {
+        self.with_child(visual_context, |child, context| child.on_tick(context))
+    }
+
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {
+        self.with_child(visual_context, |child, context| {
+            child.on_resize(size, context)
+        });
+
+        self.input_handler.on_resize(size, visual_context);
+    }
+
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        self.with_child(visual_context, |child, context| {
+            child.on_timer(timer, context)
+        }) || self.input_handler.on_timer(timer, visual_context)
+    }
+
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        self.with_child(visual_context, |child, context| {
+            child.on_message(message, context)
+        }) || self.input_handler.on_message(message, visual_context)
+    }
+
+    fn focus_first(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.is_child_focused = self.child.focus_first(direction, visual_context);
+
+        if self.is_child_focused {
+            self.input_handler.on_got_focus(visual_context);
+        }
+
+        self.is_child_focused
+    }
+
+    fn focus_next(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if !self.is_child_focused {
+            return self.focus_first(direction, visual_context);
+        }
+
+        self.is_child_focused = self.child.focus_next(direction, visual_context);
+
+        if !self.is_child_focused {
+            self.input_handler.on_lost_focus(visual_context);
+        }
+
+        self.is_child_focused
+    }
+
+    fn clear_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        if self.is_child_focused {
+            self.is_child_focused = false;
+            self.child.clear_focus(visual_context);
+            self.input_handler.on_lost_focus(visual_context);
+        }
     }
 
     fn on_key_press(
@@ -54,8 +379,10 @@ impl VisualInput for ContentVisual {
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_key_press(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_key_press(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled = self.with_focused_child(visual_context, |child, context| {
+                child.on_key_press(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -73,8 +400,10 @@ impl VisualInput for ContentVisual {
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_key_release(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_key_release(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled = self.with_focused_child(visual_context, |child, context| {
+                child.on_key_release(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -89,11 +418,16 @@ impl VisualInput for ContentVisual {
         args: &MouseEventArgs,
         visual_context: &mut dyn MutableContext,
     ) -> bool {
+        self.update_hovered(args, visual_context);
+
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_move(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_mouse_move(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_move(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -111,8 +445,11 @@ impl VisualInput for ContentVisual {
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_wheel(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_mouse_wheel(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_wheel(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -130,8 +467,11 @@ impl VisualInput for ContentVisual {
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_up(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_mouse_up(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_up(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -146,11 +486,16 @@ impl VisualInput for ContentVisual {
         args: &MouseButtonEventArgs,
         visual_context: &mut dyn MutableContext,
     ) -> bool {
+        self.is_child_pressed = self.mouse_target(args.position).is_some();
+
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_down(args, visual_context);
 
-        if !tunnel_handled && self.is_child_focused {
-            bubble_handled = self.child.on_mouse_down(args, visual_context);
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_down(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -159,16 +504,192 @@ impl VisualInput for ContentVisual {
 
         bubble_handled
     }
+
+    fn on_click(&mut self, args: &ClickEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_click(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_click_target(args.position, visual_context, |child, origin, context| {
+                    child.on_click(&args.relative_to(origin), context)
+                });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_click(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag_start(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag_start(&args.relative_to(origin), context)
+            });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag_start(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag(&mut self, args: &DragEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag(&args.relative_to(origin), context)
+            });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag_end(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag_end(&args.relative_to(origin), context)
+            });
+        }
+
+        self.is_child_pressed = false;
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag_end(args, visual_context);
+        }
+
+        bubble_handled
+    }
 }
 
 impl Draw for ContentVisual {
     fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
-        self.layout.draw(&*self.child, buffer, available_size)
+        let size = self.arrange(available_size);
+
+        self.render(buffer, size);
+
+        size
+    }
+
+    fn arrange(&self, final_size: Size) -> Size {
+        let mut arrangement = self.arrangement.borrow_mut();
+
+        if let Some(arrangement) = arrangement
+            .as_deref()
+            .filter(|_| self.arranged_size.get() == Some(final_size))
+        {
+            return arrangement.size();
+        }
+
+        let new_arrangement = self.layout.arrange(&*self.child, final_size);
+
+        // the visual is painted again around the child which moved or changed its size
+        let is_moved = arrangement.as_deref().is_none_or(|arrangement| {
+            arrangement.size() != new_arrangement.size()
+                || arrangement.visual_rect(0) != new_arrangement.visual_rect(0)
+        });
+
+        if is_moved {
+            self.is_rendered.set(false);
+        }
+
+        let size = new_arrangement.size();
+
+        *arrangement = Some(new_arrangement);
+        self.arranged_size.set(Some(final_size));
+
+        size
+    }
+
+    fn render(&self, buffer: &mut dyn WriteBuffer, _size: Size) {
+        let arrangement = self.arrangement.borrow();
+
+        let Some(arrangement) = arrangement.as_deref() else {
+            return;
+        };
+
+        if !self.is_rendered.replace(true) {
+            self.layout.render(buffer, arrangement);
+            self.child.invalidate(Invalidation::Render);
+        }
+
+        // the visual is rendered only when the child or its descendants are invalid
+        if let Some(rect) = arrangement.visual_rect(0) {
+            self.child
+                .render(&mut VirtualBuffer::new(buffer, rect), rect.as_size());
+        }
     }
 
     fn measure(&self, constraints: Size) -> Size {
-        self.layout.measure(&*self.child, constraints)
+        match self.measure_cache.get() {
+            Some((cached_constraints, size)) if cached_constraints == constraints => size,
+            _ => {
+                let size = self.layout.measure(&*self.child, constraints);
+
+                self.measure_cache.set(Some((constraints, size)));
+
+                size
+            }
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.child.is_animating()
+    }
+
+    fn invalidate(&self, invalidation: Invalidation) {
+        match invalidation {
+            Invalidation::Descendants => {}
+            Invalidation::Render => self.is_rendered.set(false),
+            Invalidation::Arrange => self.arranged_size.set(None),
+            Invalidation::Measure => {
+                self.measure_cache.set(None);
+                self.arranged_size.set(None);
+            }
+        }
     }
 }
 
-impl Visual for ContentVisual {}
+impl Visual for ContentVisual {
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a dyn Visual)) {
+        visitor(&*self.child);
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut dyn Visual)) {
+        visitor(self.child.as_mut());
+    }
+
+    fn reconcile(&mut self, previous: &dyn Visual) {
+        if let Some(previous) = previous.downcast_ref::<Self>() {
+            self.is_child_focused = previous.is_child_focused;
+            self.is_child_hovered = previous.is_child_hovered;
+            self.is_child_captured = previous.is_child_captured;
+            self.is_child_pressed = previous.is_child_pressed;
+
+            self.child.reconcile(&*previous.child);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::{any::Any, fmt};
+
+use crate::{
+    buffer::WriteBuffer,
+    input::{
+        ClickEventArgs, DragEventArgs, FocusDirection, KeyEventArgs, MouseButtonEventArgs,
+        MouseEventArgs, MouseWheelEventArgs, PasteEventArgs, VisualInput,
+    },
+    visual::{Draw, Invalidation, MutableContext, Visual},
+    Size, TimerId,
+};
+
+/// Identifies a visual in the visual tree, see [`Identify::with_id`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VisualId(String);
+
+impl VisualId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for VisualId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<String> for VisualId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for VisualId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Visual with an id, which behaves exactly like the wrapped visual.
+///
+/// Lookups by id downcast it to the type of the wrapped visual, e.g. `find::<TextBlock>("status")`.
+pub struct Identified<V> {
+    id: VisualId,
+    visual: V,
+}
+
+impl<V> Identified<V>
+where
+    V: Visual,
+{
+    pub fn new(id: impl Into<VisualId>, visual: V) -> Self {
+        Self {
+            id: id.into(),
+            visual,
+        }
+    }
+
+    pub fn visual(&self) -> &V {
+        &self.visual
+    }
+
+    pub fn visual_mut(&mut self) -> &mut V {
+        &mut self.visual
+    }
+
+    pub fn into_visual(self) -> V {
+        self.visual
+    }
+}
+
+/// Extends visuals with [`Identify::with_id`].
+pub trait Identify: Visual + Sized {
+    /// Gives the id to the visual, so it can be found in the visual tree.
+    fn with_id(self, id: impl Into<VisualId>) -> Identified<Self>;
+}
+
+impl<V> Identify for V
+where
+    V: Visual,
+{
+    fn with_id(self, id: impl Into<VisualId>) -> Identified<Self> {
+        Identified::new(id, self)
+    }
+}
+
+impl<V> VisualInput for Identified<V>
+where
+    V: Visual,
+{
+    fn on_paste(&mut self, args: &PasteEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        self.visual.on_paste(args, visual_context)
+    }
+
+    fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        self.visual.on_got_focus(visual_context);
+    }
+
+    fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        self.visual.on_lost_focus(visual_context);
+    }
+
+    fn on_mouse_enter(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        self.visual.on_mouse_enter(args, visual_context);
+    }
+
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {
+        self.visual.on_mouse_leave(visual_context);
+    }
+
+    fn on_tick(&mut self, visual_context: &mut dyn MutableContext) {
+        self.visual.on_tick(visual_context);
+    }
+
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {
+        self.visual.on_resize(size, visual_context);
+    }
+
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        self.visual.on_timer(timer, visual_context)
+    }
+
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        self.visual.on_message(message, visual_context)
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.visual.is_focusable()
+    }
+
+    fn focus_first(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.focus_first(direction, visual_context)
+    }
+
+    fn focus_next(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.focus_next(direction, visual_context)
+    }
+
+    fn clear_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        self.visual.clear_focus(visual_context);
+    }
+
+    fn on_key_press(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_key_press(args, visual_context)
+    }
+
+    fn on_key_release(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_key_release(args, visual_context)
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        args: &MouseEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_mouse_move(args, visual_context)
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        args: &MouseWheelEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_mouse_wheel(args, visual_context)
+    }
+
+    fn on_mouse_up(
+        &mut self,
+        args: &MouseButtonEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_mouse_up(args, visual_context)
+    }
+
+    fn on_mouse_down(
+        &mut self,
+        args: &MouseButtonEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_mouse_down(args, visual_context)
+    }
+
+    fn on_click(&mut self, args: &ClickEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        self.visual.on_click(args, visual_context)
+    }
+
+    fn on_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_drag_start(args, visual_context)
+    }
+
+    fn on_drag(&mut self, args: &DragEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        self.visual.on_drag(args, visual_context)
+    }
+
+    fn on_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.visual.on_drag_end(args, visual_context)
+    }
+}
+
+impl<V> Draw for Identified<V>
+where
+    V: Visual,
+{
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        self.visual.draw(buffer, available_size)
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        self.visual.measure(constraints)
+    }
+
+    fn arrange(&self, final_size: Size) -> Size {
+        self.visual.arrange(final_size)
+    }
+
+    fn render(&self, buffer: &mut dyn WriteBuffer, size: Size) {
+        self.visual.render(buffer, size);
+    }
+
+    fn is_animating(&self) -> bool {
+        self.visual.is_animating()
+    }
+
+    fn invalidate(&self, invalidation: Invalidation) {
+        self.visual.invalidate(invalidation);
+    }
+}
+
+impl<V> Visual for Identified<V>
+where
+    V: Visual,
+{
+    fn id(&self) -> Option<&VisualId> {
+        Some(&self.id)
+    }
+
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a dyn Visual)) {
+        self.visual.visit_children(visitor);
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut dyn Visual)) {
+        self.visual.visit_children_mut(visitor);
+    }
+
+    fn reconcile(&mut self, previous: &dyn Visual) {
+        self.visual.reconcile(previous);
+    }
+}
@@ -1,14 +1,77 @@
-use std::process::ExitCode;
+use std::{process::ExitCode, time::Duration};
+
+use crate::{Message, Task, TimerId};
+
+/// Part of the visual which has to be computed again before the next draw.
+///
+/// Levels are ordered by the amount of work, a visual which is arranged again paints
+/// only the descendants which are invalid or moved, so the whole visual is painted
+/// again only by [`Invalidation::Render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Invalidation {
+    /// Only some descendants of the visual have to be painted again.
+    Descendants,
+    /// The visual has to be painted again, its size and position stay the same.
+    Render,
+    /// Children of the visual have to be positioned again, its size stays the same.
+    Arrange,
+    /// Size of the visual may have changed, so it and its ancestors have to be
+    /// measured and arranged again.
+    Measure,
+}
 
 /// Provides mutable access to application state.
 pub trait MutableContext {
     /// Forces a complete redraw of the visual. This is necessary as a visual element should not
     /// be aware of its own size - this is the responsibility of the parent element.
+    ///
+    /// The same as [`MutableContext::invalidate`] with [`Invalidation::Measure`],
+    /// prefer a lower level if the size of the visual does not change.
     fn redraw(&mut self);
 
-    /// Sets the focus for the visual.
+    /// Marks the visual which handles the call as invalid, only invalid visuals
+    /// and their ancestors are computed and painted again by the next draw.
+    fn invalidate(&mut self, invalidation: Invalidation);
+
+    /// Requests keyboard focus for the visual which handles the call (`true`)
+    /// or removes focus from it (`false`).
+    ///
+    /// The request is processed by parent visuals after the handler returns.
     fn set_focus(&mut self, value: bool);
 
+    /// Captures the mouse for the visual which handles the call (`true`) or releases
+    /// the capture (`false`). While the mouse is captured, all mouse events are delivered
+    /// to the visual, even when the pointer is outside of it. Coordinates of such events
+    /// are clamped to zero on the left and top of the visual.
+    ///
+    /// The request is processed by parent visuals after the handler returns.
+    fn set_mouse_capture(&mut self, value: bool);
+
+    /// Starts the timer which is due after the delay and then, if it repeats,
+    /// after every interval of the same length. Starting a started timer restarts it.
+    ///
+    /// Due timers are delivered to the whole visual tree by [`crate::input::VisualInput::on_timer`].
+    fn start_timer(&mut self, timer: TimerId, delay: Duration, repeat: bool);
+
+    /// Stops the timer, nothing happens if it is not started.
+    fn stop_timer(&mut self, timer: TimerId);
+
+    /// Runs the future in the background, its output is delivered to the whole visual tree
+    /// by [`crate::input::VisualInput::on_message`] when it completes.
+    ///
+    /// [`crate::EventLoop`] runs the future on its own thread without an async runtime,
+    /// see [`crate::EventLoop::run`].
+    fn spawn(&mut self, task: Task);
+
+    /// Posts the message, which is delivered to the whole visual tree
+    /// by [`crate::input::VisualInput::on_message`] right after the current event,
+    /// like the output of a task which is already completed.
+    fn post(&mut self, message: Message);
+
+    /// Prints the text above the inline viewport, so it scrolls into the terminal history.
+    /// Nothing is printed when the app uses the whole screen.
+    fn print_above(&mut self, text: &str);
+
     /// Provides a proper way to terminate the process. The exit code is specified by the
     /// `ExitCode` parameter.
     fn terminate_app(&mut self, exit_code: ExitCode);
@@ -17,16 +80,40 @@ pub trait MutableContext {
 /// Represents an action on [`MutableContext`]
 pub enum MutableContextAction {
     Redraw,
+    Invalidate(Invalidation),
     SetFocus(bool),
+    SetMouseCapture(bool),
+    StartTimer {
+        timer: TimerId,
+        delay: Duration,
+        repeat: bool,
+    },
+    StopTimer(TimerId),
+    Spawn(Task),
+    Post(Message),
+    PrintAbove(String),
     Terminate(ExitCode),
 }
 
 impl MutableContextAction {
-    pub fn apply(&self, visual_context: &mut dyn MutableContext) {
+    pub fn apply(self, visual_context: &mut dyn MutableContext) {
         match self {
             MutableContextAction::Redraw => visual_context.redraw(),
-            MutableContextAction::SetFocus(value) => visual_context.set_focus(*value),
-            MutableContextAction::Terminate(exit_code) => visual_context.terminate_app(*exit_code),
+            MutableContextAction::Invalidate(invalidation) => {
+                visual_context.invalidate(invalidation)
+            }
+            MutableContextAction::SetFocus(value) => visual_context.set_focus(value),
+            MutableContextAction::SetMouseCapture(value) => visual_context.set_mouse_capture(value),
+            MutableContextAction::StartTimer {
+                timer,
+                delay,
+                repeat,
+            } => visual_context.start_timer(timer, delay, repeat),
+            MutableContextAction::StopTimer(timer) => visual_context.stop_timer(timer),
+            MutableContextAction::Spawn(task) => visual_context.spawn(task),
+            MutableContextAction::Post(message) => visual_context.post(message),
+            MutableContextAction::PrintAbove(text) => visual_context.print_above(&text),
+            MutableContextAction::Terminate(exit_code) => visual_context.terminate_app(exit_code),
         }
     }
 }
@@ -47,12 +134,132 @@ impl<'a> MutableContext for RetainedMutableContext<'a> {
         self.actions.push(MutableContextAction::SetFocus(value));
     }
 
+    fn set_mouse_capture(&mut self, value: bool) {
+        self.actions
+            .push(MutableContextAction::SetMouseCapture(value));
+    }
+
+    fn start_timer(&mut self, timer: TimerId, delay: Duration, repeat: bool) {
+        self.actions.push(MutableContextAction::StartTimer {
+            timer,
+            delay,
+            repeat,
+        });
+    }
+
+    fn stop_timer(&mut self, timer: TimerId) {
+        self.actions.push(MutableContextAction::StopTimer(timer));
+    }
+
+    fn spawn(&mut self, task: Task) {
+        self.actions.push(MutableContextAction::Spawn(task));
+    }
+
+    fn post(&mut self, message: Message) {
+        self.actions.push(MutableContextAction::Post(message));
+    }
+
+    fn print_above(&mut self, text: &str) {
+        self.actions
+            .push(MutableContextAction::PrintAbove(text.to_owned()));
+    }
+
     fn redraw(&mut self) {
         self.actions.push(MutableContextAction::Redraw);
     }
 
+    fn invalidate(&mut self, invalidation: Invalidation) {
+        self.actions
+            .push(MutableContextAction::Invalidate(invalidation));
+    }
+
     fn terminate_app(&mut self, exit_code: ExitCode) {
         self.actions
             .push(MutableContextAction::Terminate(exit_code));
     }
 }
+
+/// Context for calls to a child visual, which forwards everything to the parent context
+/// except focus and mouse capture requests, these are saved to be processed by the parent visual.
+///
+/// Invalidation of the child is saved too and the parent context is notified that its
+/// descendants are invalid, or that it has to be measured again if the child has to be.
+pub struct ChildMutableContext<'a> {
+    parent: &'a mut dyn MutableContext,
+    focus_request: Option<bool>,
+    mouse_capture_request: Option<bool>,
+    invalidation: Option<Invalidation>,
+}
+
+impl<'a> ChildMutableContext<'a> {
+    pub fn new(parent: &'a mut dyn MutableContext) -> Self {
+        Self {
+            parent,
+            focus_request: None,
+            mouse_capture_request: None,
+            invalidation: None,
+        }
+    }
+
+    /// Returns the last focus request of the child.
+    pub fn focus_request(&self) -> Option<bool> {
+        self.focus_request
+    }
+
+    /// Returns the last mouse capture request of the child.
+    pub fn mouse_capture_request(&self) -> Option<bool> {
+        self.mouse_capture_request
+    }
+
+    /// Returns the highest invalidation of the child.
+    pub fn invalidation(&self) -> Option<Invalidation> {
+        self.invalidation
+    }
+}
+
+impl<'a> MutableContext for ChildMutableContext<'a> {
+    fn redraw(&mut self) {
+        self.invalidate(Invalidation::Measure);
+    }
+
+    fn invalidate(&mut self, invalidation: Invalidation) {
+        self.invalidation = self.invalidation.max(Some(invalidation));
+
+        self.parent.invalidate(match invalidation {
+            Invalidation::Measure => Invalidation::Measure,
+            _ => Invalidation::Descendants,
+        });
+    }
+
+    fn set_focus(&mut self, value: bool) {
+        self.focus_request = Some(value);
+    }
+
+    fn set_mouse_capture(&mut self, value: bool) {
+        self.mouse_capture_request = Some(value);
+    }
+
+    fn start_timer(&mut self, timer: TimerId, delay: Duration, repeat: bool) {
+        self.parent.start_timer(timer, delay, repeat);
+    }
+
+    fn stop_timer(&mut self, timer: TimerId) {
+        self.parent.stop_timer(timer);
+    }
+
+    fn spawn(&mut self, task: Task) {
+        self.parent.spawn(task);
+    }
+
+    fn post(&mut self, message: Message) {
+        self.parent.post(message);
+    }
+
+    fn print_above(&mut self, text: &str) {
+        self.parent.print_above(text);
+    }
+
+    fn terminate_app(&mut self, exit_code: ExitCode) {
+        self.parent.terminate_app(exit_code);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::visual::{Identified, Invalidation, Visual, VisualId};
+
+/// Depth-first iterator over a visual and its descendants, see [`Visual::visit_children`].
+pub struct DepthFirst<'a> {
+    stack: Vec<&'a dyn Visual>,
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = &'a dyn Visual;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let visual = self.stack.pop()?;
+
+        let len = self.stack.len();
+        visual.visit_children(&mut |child| self.stack.push(child));
+
+        // the first child is visited next
+        self.stack[len..].reverse();
+
+        Some(visual)
+    }
+}
+
+impl<'v> dyn Visual + 'v {
+    /// Returns whether the visual has the given type, or it is [`Identified`] with this type.
+    pub fn is<T: Visual>(&self) -> bool {
+        let any = self.as_any();
+
+        any.is::<T>() || any.is::<Identified<T>>()
+    }
+
+    /// Returns the visual as the given type, unwrapping [`Identified`] visuals.
+    pub fn downcast_ref<T: Visual>(&self) -> Option<&T> {
+        let any = self.as_any();
+
+        any.downcast_ref::<T>()
+            .or_else(|| any.downcast_ref::<Identified<T>>().map(Identified::visual))
+    }
+
+    /// Returns the visual as the given type, unwrapping [`Identified`] visuals.
+    pub fn downcast_mut<T: Visual>(&mut self) -> Option<&mut T> {
+        let any = self.as_any_mut();
+
+        if any.is::<T>() {
+            any.downcast_mut::<T>()
+        } else {
+            any.downcast_mut::<Identified<T>>()
+                .map(Identified::visual_mut)
+        }
+    }
+
+    /// Iterates over the visual and its descendants in depth-first order,
+    /// parents are visited before their children.
+    pub fn depth_first(&self) -> DepthFirst<'_> {
+        DepthFirst { stack: vec![self] }
+    }
+
+    /// Finds the first visual with the given id in depth-first order, the visual itself included.
+    pub fn find_by_id(&self, id: &VisualId) -> Option<&dyn Visual> {
+        self.depth_first().find(|visual| visual.id() == Some(id))
+    }
+
+    /// Finds the visual with the given id and downcasts it to the given type.
+    pub fn find<T: Visual>(&self, id: impl Into<VisualId>) -> Option<&T> {
+        self.find_by_id(&id.into())?.downcast_ref()
+    }
+
+    /// Finds the visual with the given id and downcasts it to the given type for mutation.
+    ///
+    /// The visual and its ancestors are invalidated, so the visual is measured
+    /// and painted by the next draw.
+    pub fn find_mut<T: Visual>(&mut self, id: impl Into<VisualId>) -> Option<&mut T> {
+        let id = id.into();
+
+        for visual in self.path_to(&id)?.into_iter().rev() {
+            visual.invalidate(Invalidation::Measure);
+            visual.invalidate(Invalidation::Render);
+        }
+
+        find_by_id_mut(self, &id)?.downcast_mut()
+    }
+
+    /// Returns the visuals from this one to the visual with the given id, both included.
+    pub fn path_to(&self, id: &VisualId) -> Option<Vec<&dyn Visual>> {
+        if self.id() == Some(id) {
+            return Some(vec![self]);
+        }
+
+        let mut path = None;
+
+        self.visit_children(&mut |child| {
+            if path.is_none() {
+                path = child.path_to(id);
+            }
+        });
+
+        path.map(|mut path| {
+            path.insert(0, self);
+            path
+        })
+    }
+
+    /// Returns ancestors of the visual with the given id, from its parent up to this visual,
+    /// or `None` if there is no such descendant.
+    pub fn ancestors(&self, id: &VisualId) -> Option<Vec<&dyn Visual>> {
+        let mut path = self.path_to(id)?;
+
+        path.pop();
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+fn find_by_id_mut<'a>(visual: &'a mut dyn Visual, id: &VisualId) -> Option<&'a mut dyn Visual> {
+    if visual.id() == Some(id) {
+        return Some(visual);
+    }
+
+    let mut children = vec![];
+    visual.visit_children_mut(&mut |child| children.push(child));
+
+    children
+        .into_iter()
+        .find_map(|child| find_by_id_mut(child, id))
+}
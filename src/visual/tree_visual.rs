@@ -1,16 +1,49 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+};
+
 use crate::{
-    buffer::WriteBuffer,
+    buffer::{clear_uncovered, VirtualBuffer, WriteBuffer},
     input::{
-        KeyEventArgs, MouseButtonEventArgs, MouseEventArgs, MouseWheelEventArgs, PasteEventArgs,
-        VisualInput, VisualLeafInput,
+        ClickEventArgs, DragEventArgs, FocusDirection, KeyEventArgs, MouseButtonEventArgs,
+        MouseEventArgs, MouseWheelEventArgs, PasteEventArgs, VisualInput, VisualLeafInput,
     },
-    layout::Layout,
-    visual::{Draw, MutableContext, Visual},
-    Size,
+    layout::{Layout, VisualArrangement},
+    visual::{ChildMutableContext, Draw, Invalidation, MutableContext, Visual, VisualId},
+    Position, Rect, Size, TimerId,
 };
 
+/// Identifies a child of the [`TreeVisual`] independently of its index,
+/// it should be unique among the children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChildKey(String);
+
+impl ChildKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ChildKey {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for ChildKey {
+    fn from(key: String) -> Self {
+        Self(key)
+    }
+}
+
 /// Composes other visuals to implement the [`Visual`].
-pub struct TreeVisual<'a, L, I>
+pub struct TreeVisual<L, I>
 where
     L: Layout,
     I: VisualLeafInput,
@@ -18,19 +51,51 @@ where
     /// The drawing and measuring strategy.
     layout: L,
 
-    /// Visual which handled input events.
+    /// Index of the child which is focused or contains the focused visual.
     ///
-    /// Dont forget to validate this value when chldren changed!
-    focused: Option<&'a mut Box<dyn Visual>>,
+    /// Indices of children are updated by `remap_children` when children are reordered.
+    focused: Option<usize>,
+
+    /// Index of the child under the pointer.
+    hovered: Option<usize>,
+
+    /// Index of the child which captured the mouse or contains the visual which captured it.
+    captured: Option<usize>,
+
+    /// Index of the child which received the last mouse press.
+    pressed: Option<usize>,
 
     /// Object which handles input before children.
     input_handler: I,
 
     /// Visuals which used by layout
     children: Vec<Box<dyn Visual>>,
+
+    /// Keys of the children, which have the same indices.
+    keys: Vec<Option<ChildKey>>,
+
+    /// Arrangement of the children after the last arrange, which is used
+    /// to render them and for hit testing.
+    arrangement: RefCell<Option<Box<dyn VisualArrangement>>>,
+
+    /// Whether each child has to be painted by the next render.
+    invalid_children: RefCell<Vec<bool>>,
+
+    /// Regions which children covered before they moved, shrank or were removed,
+    /// the next render clears the parts which are not covered by children anymore.
+    stale_rects: RefCell<Vec<Rect>>,
+
+    /// Constraints and result of the last measure.
+    measure_cache: Cell<Option<(Size, Size)>>,
+
+    /// Final size of the last arrange, `None` if the children have to be arranged again.
+    arranged_size: Cell<Option<Size>>,
+
+    /// Whether the tree was painted and only invalid children have to be painted again.
+    is_rendered: Cell<bool>,
 }
 
-impl<'a, L, I> TreeVisual<'a, L, I>
+impl<L, I> TreeVisual<L, I>
 where
     L: Layout,
     I: VisualLeafInput,
@@ -38,14 +103,48 @@ where
     pub fn new(layout: L, input_handler: I, children: Vec<Box<dyn Visual>>) -> Self {
         Self {
             layout,
+            invalid_children: RefCell::new(vec![false; children.len()]),
+            keys: vec![None; children.len()],
             children,
             input_handler,
             focused: None,
+            hovered: None,
+            captured: None,
+            pressed: None,
+            arrangement: RefCell::new(None),
+            stale_rects: RefCell::new(vec![]),
+            measure_cache: Cell::new(None),
+            arranged_size: Cell::new(None),
+            is_rendered: Cell::new(false),
         }
     }
 
     pub fn with_children(mut self, children: Vec<Box<dyn Visual>>) -> Self {
+        self.keys = vec![None; children.len()];
         self.children = children;
+        self.remap_children(|_| None);
+        self
+    }
+
+    /// Replaces children by the given children with keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the keys are not unique.
+    pub fn with_keyed_children(mut self, children: Vec<(ChildKey, Box<dyn Visual>)>) -> Self {
+        (self.keys, self.children) = children
+            .into_iter()
+            .map(|(key, child)| (Some(key), child))
+            .unzip();
+
+        assert!(
+            self.keys
+                .iter()
+                .enumerate()
+                .all(|(index, key)| !self.keys[..index].contains(key)),
+            "keys of children are not unique"
+        );
+        self.remap_children(|_| None);
         self
     }
 
@@ -58,9 +157,529 @@ where
     pub fn children(&self) -> &Vec<Box<dyn Visual>> {
         &self.children
     }
+
+    /// Returns children for mutation, all of them are measured and painted by the next draw.
+    pub fn children_mut(&mut self) -> &mut [Box<dyn Visual>] {
+        for index in 0..self.children.len() {
+            self.invalidate_changed_child(index);
+        }
+
+        &mut self.children
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn child(&self, index: usize) -> Option<&dyn Visual> {
+        self.children.get(index).map(|child| &**child)
+    }
+
+    /// Returns the child for mutation, it is measured and painted by the next draw.
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut dyn Visual> {
+        if index < self.children.len() {
+            self.invalidate_changed_child(index);
+        }
+
+        match self.children.get_mut(index) {
+            Some(child) => Some(child.as_mut()),
+            None => None,
+        }
+    }
+
+    /// Returns key of the child with the given index, if the child has a key.
+    pub fn key(&self, index: usize) -> Option<&ChildKey> {
+        self.keys.get(index)?.as_ref()
+    }
+
+    /// Returns index of the child with the given key.
+    pub fn index_of(&self, key: &ChildKey) -> Option<usize> {
+        self.keys.iter().position(|k| k.as_ref() == Some(key))
+    }
+
+    pub fn child_by_key(&self, key: &ChildKey) -> Option<&dyn Visual> {
+        self.child(self.index_of(key)?)
+    }
+
+    /// Returns the child with the given key for mutation,
+    /// it is measured and painted by the next draw.
+    pub fn child_by_key_mut(&mut self, key: &ChildKey) -> Option<&mut dyn Visual> {
+        self.child_mut(self.index_of(key)?)
+    }
+
+    /// Iterates over children in their order together with their keys.
+    pub fn keyed_children(&self) -> impl Iterator<Item = (Option<&ChildKey>, &dyn Visual)> {
+        self.keys
+            .iter()
+            .zip(self.children.iter())
+            .map(|(key, child)| (key.as_ref(), &**child))
+    }
+
+    pub fn push(&mut self, child: Box<dyn Visual>) {
+        self.insert_child(self.children.len(), None, child);
+    }
+
+    /// Adds the child with the key after the other children.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a child with the key already exists.
+    pub fn push_keyed(&mut self, key: impl Into<ChildKey>, child: Box<dyn Visual>) {
+        self.insert_child(self.children.len(), Some(key.into()), child);
+    }
+
+    /// Inserts the child at the given index, children after it are shifted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the amount of children.
+    pub fn insert(&mut self, index: usize, child: Box<dyn Visual>) {
+        self.insert_child(index, None, child);
+    }
+
+    /// Inserts the child with the key at the given index, children after it are shifted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the amount of children,
+    /// or if a child with the key already exists.
+    pub fn insert_keyed(&mut self, index: usize, key: impl Into<ChildKey>, child: Box<dyn Visual>) {
+        self.insert_child(index, Some(key.into()), child);
+    }
+
+    /// Removes the child at the given index and returns it.
+    ///
+    /// If the child was focused, it loses focus and the focus moves to the next child
+    /// or to the previous one, the tree loses focus if no child can take it.
+    /// The mouse capture of the child is released.
+    pub fn remove(
+        &mut self,
+        index: usize,
+        visual_context: &mut dyn MutableContext,
+    ) -> Option<Box<dyn Visual>> {
+        if index >= self.children.len() {
+            return None;
+        }
+
+        let was_focused = self.release_child(index, visual_context);
+
+        self.keys.remove(index);
+        let child = self.children.remove(index);
+
+        self.remap_children(|i| match i.cmp(&index) {
+            Ordering::Less => Some(i),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(i - 1),
+        });
+
+        if was_focused {
+            self.focus_neighbour(index, visual_context);
+        }
+
+        Some(child)
+    }
+
+    pub fn remove_by_key(
+        &mut self,
+        key: &ChildKey,
+        visual_context: &mut dyn MutableContext,
+    ) -> Option<Box<dyn Visual>> {
+        self.remove(self.index_of(key)?, visual_context)
+    }
+
+    /// Replaces the child at the given index, keeping its key, and returns the previous one.
+    ///
+    /// If the previous child was focused, it loses focus and the focus moves to the new child
+    /// or to its neighbours, like when the child is removed. The mouse capture of the previous
+    /// child is released.
+    pub fn replace(
+        &mut self,
+        index: usize,
+        child: Box<dyn Visual>,
+        visual_context: &mut dyn MutableContext,
+    ) -> Option<Box<dyn Visual>> {
+        if index >= self.children.len() {
+            return None;
+        }
+
+        let was_focused = self.release_child(index, visual_context);
+        let previous = std::mem::replace(&mut self.children[index], child);
+
+        self.remap_children(|i| (i != index).then_some(i));
+
+        if was_focused {
+            self.focus_neighbour(index, visual_context);
+        }
+
+        Some(previous)
+    }
+
+    /// Moves the child to the given index, children between the indices are shifted.
+    /// Children keep their focus, mouse capture and other state.
+    pub fn move_child(&mut self, from: usize, to: usize) {
+        if from >= self.children.len() || to >= self.children.len() || from == to {
+            return;
+        }
+
+        let key = self.keys.remove(from);
+        let child = self.children.remove(from);
+
+        self.keys.insert(to, key);
+        self.children.insert(to, child);
+
+        self.remap_children(|i| {
+            Some(if i == from {
+                to
+            } else if from < to && (from..=to).contains(&i) {
+                i - 1
+            } else if to < from && (to..from).contains(&i) {
+                i + 1
+            } else {
+                i
+            })
+        });
+    }
+
+    /// Returns index of the child which is focused or contains the focused visual.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// Returns region of the child after the last arrange in coordinates of the tree,
+    /// or `None` if the child was not arranged.
+    pub fn child_rect(&self, index: usize) -> Option<Rect> {
+        self.arrangement
+            .borrow()
+            .as_ref()
+            .and_then(|arrangement| arrangement.visual_rect(index))
+    }
+
+    fn insert_child(&mut self, index: usize, key: Option<ChildKey>, child: Box<dyn Visual>) {
+        assert!(
+            key.is_none() || !self.keys.contains(&key),
+            "child with the key {key:?} already exists"
+        );
+
+        self.keys.insert(index, key);
+        self.children.insert(index, child);
+
+        self.remap_children(|i| Some(if i >= index { i + 1 } else { i }));
+    }
+
+    /// Removes focus, hover and mouse capture from the child which leaves the tree.
+    /// Returns whether the child was focused.
+    fn release_child(&mut self, index: usize, visual_context: &mut dyn MutableContext) -> bool {
+        let was_captured = self.captured == Some(index);
+
+        if was_captured || self.hovered == Some(index) {
+            self.children[index].on_mouse_leave(visual_context);
+        }
+
+        if was_captured {
+            visual_context.set_mouse_capture(false);
+        }
+
+        let was_focused = self.focused == Some(index);
+
+        if was_focused {
+            self.children[index].clear_focus(visual_context);
+        }
+
+        was_focused
+    }
+
+    /// Gives focus to the first child from the index which takes it, looking forward
+    /// and then backward, the tree loses focus if there is no such child.
+    fn focus_neighbour(&mut self, index: usize, visual_context: &mut dyn MutableContext) {
+        let candidates = (index..self.children.len())
+            .map(|i| (i, FocusDirection::Next))
+            .chain((0..index).rev().map(|i| (i, FocusDirection::Previous)));
+
+        for (i, direction) in candidates {
+            if self.children[i].focus_first(direction, visual_context) {
+                self.focused = Some(i);
+
+                return;
+            }
+        }
+
+        self.input_handler.on_lost_focus(visual_context);
+        visual_context.set_focus(false);
+    }
+
+    /// Updates indices of children after they were added, removed or reordered,
+    /// the function returns the new index of a child or `None` if it was removed.
+    ///
+    /// The whole tree is measured, arranged and painted by the next draw.
+    fn remap_children(&mut self, f: impl Fn(usize) -> Option<usize>) {
+        self.focused = self.focused.and_then(&f);
+        self.hovered = self.hovered.and_then(&f);
+        self.captured = self.captured.and_then(&f);
+        self.pressed = self.pressed.and_then(&f);
+
+        // the arrangement still has the regions of the children before the change
+        if let Some(arrangement) = self.arrangement.get_mut().take() {
+            let count = self.invalid_children.get_mut().len();
+
+            self.stale_rects
+                .get_mut()
+                .extend((0..count).filter_map(|index| arrangement.visual_rect(index)));
+        }
+
+        self.invalid_children = RefCell::new(vec![false; self.children.len()]);
+        self.invalidate(Invalidation::Measure);
+        self.invalidate(Invalidation::Render);
+    }
+
+    /// Marks the child which may be changed by a mutable reference to be measured
+    /// and painted by the next draw.
+    fn invalidate_changed_child(&mut self, index: usize) {
+        self.children[index].invalidate(Invalidation::Render);
+        self.invalidate_child(index, Invalidation::Measure);
+    }
+
+    /// Calls the function for the focused child, if any.
+    fn with_focused<R: Default>(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, &mut dyn MutableContext) -> R,
+    ) -> R {
+        match self.focused {
+            Some(focused) => self.with_child(focused, visual_context, f),
+            None => R::default(),
+        }
+    }
+
+    /// Returns index and origin of the child drawn at the given position.
+    fn hit(&self, position: Position) -> Option<(usize, Position)> {
+        let arrangement = self.arrangement.borrow();
+        let arrangement = arrangement.as_ref()?;
+        let index = arrangement
+            .visual_hit(position)
+            .filter(|index| *index < self.children.len())?;
+
+        Some((index, arrangement.visual_rect(index)?.as_position()))
+    }
+
+    /// Returns origin of the child after the last draw.
+    fn origin(&self, index: usize) -> Position {
+        self.arrangement
+            .borrow()
+            .as_ref()
+            .and_then(|arrangement| arrangement.visual_rect(index))
+            .map(|rect| rect.as_position())
+            .unwrap_or_default()
+    }
+
+    /// Returns index and origin of the child which captured the mouse or,
+    /// if there is none, of the child drawn at the given position.
+    fn mouse_target(&self, position: Position) -> Option<(usize, Position)> {
+        match self.captured {
+            Some(captured) => Some((captured, self.origin(captured))),
+            None => self.hit(position),
+        }
+    }
+
+    /// Calls the function for the target child of the mouse event at the given position.
+    /// Returns `false` if there is no such child.
+    fn with_mouse_target(
+        &mut self,
+        position: Position,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        match self.mouse_target(position) {
+            Some((index, origin)) => self.with_child(index, visual_context, |child, context| {
+                f(child, origin, context)
+            }),
+            None => false,
+        }
+    }
+
+    /// Calls the function for the target child of the click, which has to be the child
+    /// which received the press. Returns `false` if there is no such child.
+    fn with_click_target(
+        &mut self,
+        position: Position,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        let pressed = self.pressed.take();
+
+        match self.mouse_target(position) {
+            Some((index, origin)) if Some(index) == pressed => {
+                self.with_child(index, visual_context, |child, context| {
+                    f(child, origin, context)
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Calls the function for the child which captured the mouse or received the press
+    /// which started the drag. Returns `false` if there is no such child.
+    fn with_drag_target(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, Position, &mut dyn MutableContext) -> bool,
+    ) -> bool {
+        match self.captured.or(self.pressed) {
+            Some(index) => {
+                let origin = self.origin(index);
+
+                self.with_child(index, visual_context, |child, context| {
+                    f(child, origin, context)
+                })
+            }
+            None => false,
+        }
+    }
+
+    /// Sends leave and enter events when the child under the pointer changes.
+    fn update_hovered(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        // the child which captured the mouse stays hovered until the capture is released
+        if self.captured.is_some() {
+            return;
+        }
+
+        let hit = self.hit(args.position);
+
+        if hit.map(|(index, _)| index) == self.hovered {
+            return;
+        }
+
+        if let Some(hovered) = self.hovered.take() {
+            self.with_child(hovered, visual_context, |child, context| {
+                child.on_mouse_leave(context)
+            });
+        }
+
+        if let Some((index, origin)) = hit {
+            self.hovered = Some(index);
+            self.with_child(index, visual_context, |child, context| {
+                child.on_mouse_enter(&args.relative_to(origin), context)
+            });
+        }
+    }
+
+    /// Calls the function for the child and processes focus request of the child.
+    fn with_child<R>(
+        &mut self,
+        index: usize,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, &mut dyn MutableContext) -> R,
+    ) -> R {
+        let mut context = ChildMutableContext::new(visual_context);
+        let result = f(&mut *self.children[index], &mut context);
+
+        let (focus_request, mouse_capture_request, invalidation) = (
+            context.focus_request(),
+            context.mouse_capture_request(),
+            context.invalidation(),
+        );
+
+        if let Some(invalidation) = invalidation {
+            self.invalidate_child(index, invalidation);
+        }
+
+        if let Some(value) = focus_request {
+            self.process_focus_request(index, value, visual_context);
+        }
+
+        if let Some(value) = mouse_capture_request {
+            self.process_mouse_capture_request(index, value, visual_context);
+        }
+
+        result
+    }
+
+    /// Marks the child to be painted by the next render,
+    /// children are arranged again if the child has to be.
+    fn invalidate_child(&mut self, index: usize, invalidation: Invalidation) {
+        self.invalid_children.get_mut()[index] = true;
+
+        if invalidation > Invalidation::Descendants {
+            self.children[index].invalidate(invalidation);
+        }
+
+        match invalidation {
+            Invalidation::Measure => self.invalidate(Invalidation::Measure),
+            Invalidation::Arrange => self.invalidate(Invalidation::Arrange),
+            _ => {}
+        }
+    }
+
+    /// Updates the child which captured the mouse and passes the request to the parent.
+    fn process_mouse_capture_request(
+        &mut self,
+        index: usize,
+        value: bool,
+        visual_context: &mut dyn MutableContext,
+    ) {
+        if value {
+            self.captured = Some(index);
+            visual_context.set_mouse_capture(true);
+        } else if self.captured == Some(index) {
+            self.captured = None;
+            visual_context.set_mouse_capture(false);
+        }
+    }
+
+    /// Updates the focused child after the child requested focus change
+    /// and passes the request to the parent.
+    fn process_focus_request(
+        &mut self,
+        index: usize,
+        value: bool,
+        visual_context: &mut dyn MutableContext,
+    ) {
+        if !value {
+            // the focus is removed from the whole path by the root
+            if self.focused == Some(index) {
+                visual_context.set_focus(false);
+            }
+
+            return;
+        }
+
+        match self.focused {
+            Some(focused) if focused == index => {}
+            Some(focused) => {
+                // the tree keeps focus, so only its children are notified
+                self.children[focused].clear_focus(visual_context);
+                self.focused = Some(index);
+                self.children[index].on_got_focus(visual_context);
+            }
+            // the tree and the child are notified by the parent, which gives focus to the tree
+            None => self.focused = Some(index),
+        }
+
+        visual_context.set_focus(true);
+    }
+}
+
+impl<L, I> TreeVisual<L, I>
+where
+    L: Layout + 'static,
+    I: VisualLeafInput + 'static,
+{
+    /// Finds the descendant with the given id and downcasts it to the given type.
+    pub fn find<T: Visual>(&self, id: impl Into<VisualId>) -> Option<&T> {
+        (self as &dyn Visual).find(id)
+    }
+
+    /// Finds the descendant with the given id and downcasts it to the given type for mutation,
+    /// it is measured and painted by the next draw.
+    pub fn find_mut<T: Visual>(&mut self, id: impl Into<VisualId>) -> Option<&mut T> {
+        (self as &mut dyn Visual).find_mut(id)
+    }
 }
 
-impl<'a, L, I> VisualInput for TreeVisual<'a, L, I>
+impl<L, I> VisualInput for TreeVisual<L, I>
 where
     L: Layout,
     I: VisualLeafInput,
@@ -70,9 +689,9 @@ where
         let tunnel_handled = self.input_handler.tunnel_paste(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_paste(args, visual_context);
-            }
+            bubble_handled = self.with_focused(visual_context, |focused, context| {
+                focused.on_paste(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -85,16 +704,124 @@ where
     fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {
         self.input_handler.on_got_focus(visual_context);
 
-        for child in self.children.iter_mut() {
-            child.on_got_focus(visual_context);
+        if let Some(focused) = self.focused {
+            self.children[focused].on_got_focus(visual_context);
         }
     }
 
     fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {
         self.input_handler.on_lost_focus(visual_context);
 
-        for child in self.children.iter_mut() {
-            child.on_lost_focus(visual_context);
+        if let Some(focused) = self.focused {
+            self.children[focused].on_lost_focus(visual_context);
+        }
+    }
+
+    fn on_mouse_enter(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        // children are entered by the following mouse move
+        self.input_handler.on_mouse_enter(args, visual_context);
+    }
+
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {
+        self.input_handler.on_mouse_leave(visual_context);
+
+        if let Some(hovered) = self.hovered.take() {
+            self.with_child(hovered, visual_context, |child, context| {
+                child.on_mouse_leave(context)
+            });
+        }
+    }
+
+    fn on_tick(&mut self, visual_context: &mut dyn MutableContext) {
+        for index in 0..self.children.len() {
+            self.with_child(index, visual_context, |child, context| {
+                child.on_tick(context)
+            });
+        }
+    }
+
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {
+        for index in 0..self.children.len() {
+            self.with_child(index, visual_context, |child, context| {
+                child.on_resize(size, context)
+            });
+        }
+
+        self.input_handler.on_resize(size, visual_context);
+    }
+
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        for index in 0..self.children.len() {
+            if self.with_child(index, visual_context, |child, context| {
+                child.on_timer(timer, context)
+            }) {
+                return true;
+            }
+        }
+
+        self.input_handler.on_timer(timer, visual_context)
+    }
+
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        for index in 0..self.children.len() {
+            if self.with_child(index, visual_context, |child, context| {
+                child.on_message(message, context)
+            }) {
+                return true;
+            }
+        }
+
+        self.input_handler.on_message(message, visual_context)
+    }
+
+    fn focus_first(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        for index in direction.indices_after(None, self.children.len()) {
+            if self.children[index].focus_first(direction, visual_context) {
+                self.focused = Some(index);
+                self.input_handler.on_got_focus(visual_context);
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn focus_next(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let Some(focused) = self.focused else {
+            return self.focus_first(direction, visual_context);
+        };
+
+        if self.children[focused].focus_next(direction, visual_context) {
+            return true;
+        }
+
+        for index in direction.indices_after(Some(focused), self.children.len()) {
+            if self.children[index].focus_first(direction, visual_context) {
+                self.focused = Some(index);
+
+                return true;
+            }
+        }
+
+        self.focused = None;
+        self.input_handler.on_lost_focus(visual_context);
+
+        false
+    }
+
+    fn clear_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        if let Some(focused) = self.focused.take() {
+            self.children[focused].clear_focus(visual_context);
+            self.input_handler.on_lost_focus(visual_context);
         }
     }
 
@@ -107,9 +834,9 @@ where
         let tunnel_handled = self.input_handler.tunnel_key_press(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_key_press(args, visual_context);
-            }
+            bubble_handled = self.with_focused(visual_context, |focused, context| {
+                focused.on_key_press(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -128,9 +855,9 @@ where
         let tunnel_handled = self.input_handler.tunnel_key_release(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_key_release(args, visual_context);
-            }
+            bubble_handled = self.with_focused(visual_context, |focused, context| {
+                focused.on_key_release(args, context)
+            });
         }
 
         if !bubble_handled {
@@ -145,13 +872,16 @@ where
         args: &MouseEventArgs,
         visual_context: &mut dyn MutableContext,
     ) -> bool {
+        self.update_hovered(args, visual_context);
+
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_move(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_mouse_move(args, visual_context);
-            }
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_move(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -170,9 +900,10 @@ where
         let tunnel_handled = self.input_handler.tunnel_mouse_wheel(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_mouse_wheel(args, visual_context);
-            }
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_wheel(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -191,9 +922,10 @@ where
         let tunnel_handled = self.input_handler.tunnel_mouse_up(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_mouse_up(args, visual_context);
-            }
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_up(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -208,13 +940,16 @@ where
         args: &MouseButtonEventArgs,
         visual_context: &mut dyn MutableContext,
     ) -> bool {
+        self.pressed = self.mouse_target(args.position).map(|(index, _)| index);
+
         let mut bubble_handled = false;
         let tunnel_handled = self.input_handler.tunnel_mouse_down(args, visual_context);
 
         if !tunnel_handled {
-            if let Some(ref mut focused) = self.focused {
-                bubble_handled = focused.on_mouse_down(args, visual_context);
-            }
+            bubble_handled =
+                self.with_mouse_target(args.position, visual_context, |child, origin, context| {
+                    child.on_mouse_down(&args.relative_to(origin), context)
+                });
         }
 
         if !bubble_handled {
@@ -223,27 +958,569 @@ where
 
         bubble_handled
     }
+
+    fn on_click(&mut self, args: &ClickEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_click(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled =
+                self.with_click_target(args.position, visual_context, |child, origin, context| {
+                    child.on_click(&args.relative_to(origin), context)
+                });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_click(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag_start(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag_start(&args.relative_to(origin), context)
+            });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag_start(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag(&mut self, args: &DragEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag(&args.relative_to(origin), context)
+            });
+        }
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag(args, visual_context);
+        }
+
+        bubble_handled
+    }
+
+    fn on_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let mut bubble_handled = false;
+        let tunnel_handled = self.input_handler.tunnel_drag_end(args, visual_context);
+
+        if !tunnel_handled {
+            bubble_handled = self.with_drag_target(visual_context, |child, origin, context| {
+                child.on_drag_end(&args.relative_to(origin), context)
+            });
+        }
+
+        self.pressed = None;
+
+        if !bubble_handled {
+            bubble_handled = self.input_handler.bubble_drag_end(args, visual_context);
+        }
+
+        bubble_handled
+    }
 }
 
-impl<'a, L, I> Draw for TreeVisual<'a, L, I>
+impl<L, I> Draw for TreeVisual<L, I>
 where
     L: Layout,
     I: VisualLeafInput,
 {
     fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
-        let arrange = self.layout.draw(&self.children, buffer, available_size);
+        let size = self.arrange(available_size);
+
+        self.render(buffer, size);
+
+        size
+    }
+
+    fn arrange(&self, final_size: Size) -> Size {
+        let mut arrangement = self.arrangement.borrow_mut();
+
+        if let Some(arrangement) = arrangement
+            .as_deref()
+            .filter(|_| self.arranged_size.get() == Some(final_size))
+        {
+            return arrangement.size();
+        }
+
+        let new_arrangement = self.layout.arrange(&self.children, final_size);
+        let mut invalid_children = self.invalid_children.borrow_mut();
+        let mut stale_rects = self.stale_rects.borrow_mut();
+
+        // children which moved or changed their size are painted again
+        for (index, child) in self.children.iter().enumerate() {
+            let rect = arrangement
+                .as_deref()
+                .and_then(|arrangement| arrangement.visual_rect(index));
+
+            if rect != new_arrangement.visual_rect(index) {
+                invalid_children[index] = true;
+                child.invalidate(Invalidation::Render);
+                stale_rects.extend(rect);
+            }
+        }
+
+        let size = new_arrangement.size();
 
-        arrange.size()
+        *arrangement = Some(new_arrangement);
+        self.arranged_size.set(Some(final_size));
+
+        size
+    }
+
+    fn render(&self, buffer: &mut dyn WriteBuffer, size: Size) {
+        let arrangement = self.arrangement.borrow();
+        let mut invalid_children = self.invalid_children.borrow_mut();
+        let is_rendered = self.is_rendered.replace(true);
+
+        let Some(arrangement) = arrangement.as_deref() else {
+            return;
+        };
+
+        let mut stale_rects = std::mem::take(&mut *self.stale_rects.borrow_mut());
+
+        // the whole tree is painted, including gaps between children
+        if !is_rendered {
+            stale_rects.push(Rect::from(size));
+        }
+
+        let stale_rects: Vec<Rect> = stale_rects
+            .into_iter()
+            .map(|rect| rect.intersection(Rect::from(size)))
+            .collect();
+        let child_rects: Vec<Rect> = (0..self.children.len())
+            .filter_map(|index| arrangement.visual_rect(index))
+            .collect();
+
+        clear_uncovered(buffer, &stale_rects, &child_rects).expect("Cannot write to buffer");
+
+        for (index, child) in self.children.iter().enumerate() {
+            if !is_rendered {
+                child.invalidate(Invalidation::Render);
+            } else if !invalid_children[index] {
+                continue;
+            }
+
+            // the child was not arranged, e.g. there was no room for it
+            if let Some(rect) = arrangement.visual_rect(index) {
+                child.render(&mut VirtualBuffer::new(buffer, rect), rect.as_size());
+            }
+        }
+
+        // animating children are painted by the next render too
+        for (is_invalid, child) in invalid_children.iter_mut().zip(self.children.iter()) {
+            *is_invalid = child.is_animating();
+        }
     }
 
     fn measure(&self, constraints: Size) -> Size {
-        self.layout.measure(&self.children, constraints)
+        match self.measure_cache.get() {
+            Some((cached_constraints, size)) if cached_constraints == constraints => size,
+            _ => {
+                let size = self.layout.measure(&self.children, constraints);
+
+                self.measure_cache.set(Some((constraints, size)));
+
+                size
+            }
+        }
+    }
+
+    fn is_animating(&self) -> bool {
+        self.children.iter().any(|child| child.is_animating())
+    }
+
+    fn invalidate(&self, invalidation: Invalidation) {
+        match invalidation {
+            Invalidation::Descendants => {}
+            Invalidation::Render => self.is_rendered.set(false),
+            Invalidation::Arrange => self.arranged_size.set(None),
+            Invalidation::Measure => {
+                self.measure_cache.set(None);
+                self.arranged_size.set(None);
+            }
+        }
     }
 }
 
-impl<'a, L, I> Visual for TreeVisual<'a, L, I>
+impl<L, I> Visual for TreeVisual<L, I>
 where
-    L: Layout,
-    I: VisualLeafInput,
+    L: Layout + 'static,
+    I: VisualLeafInput + 'static,
 {
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a dyn Visual)) {
+        for child in &self.children {
+            visitor(&**child);
+        }
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut dyn Visual)) {
+        for child in &mut self.children {
+            visitor(child.as_mut());
+        }
+    }
+
+    /// Children are matched by their keys, children without keys are matched by index.
+    fn reconcile(&mut self, previous: &dyn Visual) {
+        let Some(previous) = previous.downcast_ref::<Self>() else {
+            return;
+        };
+
+        // new index of each previous child
+        let mut indices = vec![None; previous.children.len()];
+
+        for (index, child) in self.children.iter_mut().enumerate() {
+            let previous_index = match &self.keys[index] {
+                Some(key) => previous.index_of(key),
+                None => (index < previous.len() && previous.keys[index].is_none()).then_some(index),
+            };
+
+            if let Some(previous_index) = previous_index {
+                child.reconcile(&*previous.children[previous_index]);
+                indices[previous_index] = Some(index);
+            }
+        }
+
+        self.focused = previous.focused.and_then(|i| indices[i]);
+        self.hovered = previous.hovered.and_then(|i| indices[i]);
+        self.captured = previous.captured.and_then(|i| indices[i]);
+        self.pressed = previous.pressed.and_then(|i| indices[i]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+    use crate::{
+        buffer::ReadBuffer,
+        input::{EmptyVisualLeafInput, KeyModifiers, MouseButton},
+        layout::VStackLayout,
+        style::{Attribute, Attributes, Color, Style},
+        visual::{MutableContextAction, RetainedMutableContext, TextBlock},
+    };
+
+    /// Buffer which keeps only symbols of the cells.
+    struct Screen {
+        rows: Vec<Vec<char>>,
+    }
+
+    impl Screen {
+        fn new(size: Size) -> Self {
+            Self {
+                rows: vec![vec!['.'; size.width as usize]; size.height as usize],
+            }
+        }
+
+        fn lines(&self) -> Vec<String> {
+            self.rows.iter().map(|row| row.iter().collect()).collect()
+        }
+    }
+
+    impl WriteBuffer for Screen {
+        fn write_buffer(&mut self, _: Position, _: &dyn ReadBuffer) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn write_symbols(
+            &mut self,
+            position: Position,
+            symbols: &str,
+            _: Style,
+        ) -> Result<(), Box<dyn Error>> {
+            let row = &mut self.rows[position.y as usize];
+
+            for (x, symbol) in (position.x as usize..).zip(symbols.chars()) {
+                row[x] = symbol;
+            }
+
+            Ok(())
+        }
+
+        fn set_forecolor(&mut self, _: Position, _: Color) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn set_backcolor(&mut self, _: Position, _: Color) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn set_underline_color(&mut self, _: Position, _: Color) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn set_symbol(&mut self, position: Position, symbol: char) -> Result<(), Box<dyn Error>> {
+            self.rows[position.y as usize][position.x as usize] = symbol;
+
+            Ok(())
+        }
+
+        fn set_style(&mut self, _: Position, _: Style) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn set_attribute(&mut self, _: Position, _: Attribute) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn set_attributes(&mut self, _: Position, _: Attributes) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn clear(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+
+        fn clear_region(&mut self, _: Rect) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    /// Focusable visual with state which is taken from the previous visual by reconcile,
+    /// it captures the mouse when pressed.
+    struct Field {
+        state: u32,
+        is_focused: bool,
+        is_hovered: bool,
+    }
+
+    impl Field {
+        fn boxed(state: u32) -> Box<dyn Visual> {
+            Box::new(Self {
+                state,
+                is_focused: false,
+                is_hovered: false,
+            })
+        }
+    }
+
+    impl VisualInput for Field {
+        fn is_focusable(&self) -> bool {
+            true
+        }
+
+        fn on_got_focus(&mut self, _: &mut dyn MutableContext) {
+            self.is_focused = true;
+        }
+
+        fn on_lost_focus(&mut self, _: &mut dyn MutableContext) {
+            self.is_focused = false;
+        }
+
+        fn on_mouse_enter(&mut self, _: &MouseEventArgs, _: &mut dyn MutableContext) {
+            self.is_hovered = true;
+        }
+
+        fn on_mouse_leave(&mut self, _: &mut dyn MutableContext) {
+            self.is_hovered = false;
+        }
+
+        fn on_mouse_down(
+            &mut self,
+            _: &MouseButtonEventArgs,
+            visual_context: &mut dyn MutableContext,
+        ) -> bool {
+            visual_context.set_mouse_capture(true);
+            true
+        }
+    }
+
+    impl Draw for Field {
+        fn draw(&self, _: &mut dyn WriteBuffer, _: Size) -> Size {
+            Size::new(1, 1)
+        }
+
+        fn measure(&self, _: Size) -> Size {
+            Size::new(1, 1)
+        }
+    }
+
+    impl Visual for Field {
+        fn reconcile(&mut self, previous: &dyn Visual) {
+            if let Some(previous) = previous.downcast_ref::<Field>() {
+                self.state = previous.state;
+            }
+        }
+    }
+
+    fn field(visual: Option<&dyn Visual>) -> &Field {
+        visual.and_then(|visual| visual.downcast_ref()).unwrap()
+    }
+
+    fn states(tree: &TreeVisual<VStackLayout, EmptyVisualLeafInput>) -> Vec<u32> {
+        (0..tree.len())
+            .map(|index| field(tree.child(index)).state)
+            .collect()
+    }
+
+    fn text(text: &str) -> Box<dyn Visual> {
+        Box::new(TextBlock::new(text.to_owned()))
+    }
+
+    fn stack(children: Vec<Box<dyn Visual>>) -> TreeVisual<VStackLayout, EmptyVisualLeafInput> {
+        TreeVisual::new(VStackLayout, EmptyVisualLeafInput, children)
+    }
+
+    #[test]
+    fn clears_cells_of_removed_child() {
+        let size = Size::new(4, 2);
+        let mut screen = Screen::new(size);
+        let mut tree = stack(vec![text("aaaa"), text("bb"), text("cccc")]);
+
+        tree.draw(&mut screen, size);
+        tree.remove(0, &mut RetainedMutableContext::new(&mut vec![]));
+        tree.draw(&mut screen, size);
+
+        assert_eq!(screen.lines(), ["bb  ", "cccc"]);
+    }
+
+    #[test]
+    fn clears_cells_of_shrunk_child() {
+        let size = Size::new(4, 3);
+        let mut screen = Screen::new(size);
+        let inner = stack(vec![text("aaaa"), text("bb")]);
+        let mut tree = stack(vec![Box::new(inner), text("xxxx")]);
+
+        tree.draw(&mut screen, size);
+        tree.child_mut(0)
+            .and_then(|child| {
+                child.downcast_mut::<TreeVisual<VStackLayout, EmptyVisualLeafInput>>()
+            })
+            .unwrap()
+            .replace(0, text("a"), &mut RetainedMutableContext::new(&mut vec![]));
+        tree.draw(&mut screen, size);
+
+        assert_eq!(screen.lines(), ["a   ", "bb  ", "xxxx"]);
+    }
+
+    #[test]
+    fn moves_focus_from_removed_child_to_next_one() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tree = stack(vec![Field::boxed(0), Field::boxed(1), Field::boxed(2)]);
+
+        tree.focus_first(FocusDirection::Next, context);
+        tree.focus_next(FocusDirection::Next, context);
+
+        let removed = tree.remove(1, context).unwrap();
+
+        assert!(!field(Some(&*removed)).is_focused);
+        assert_eq!(tree.focused(), Some(1));
+        assert!(field(tree.child(1)).is_focused);
+    }
+
+    #[test]
+    fn moves_focus_from_removed_last_child_to_previous_one() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tree = stack(vec![Field::boxed(0), text("a"), Field::boxed(2)]);
+
+        tree.focus_first(FocusDirection::Previous, context);
+        tree.remove(2, context);
+
+        assert_eq!(tree.focused(), Some(0));
+        assert!(field(tree.child(0)).is_focused);
+    }
+
+    #[test]
+    fn loses_focus_when_no_child_takes_it() {
+        let mut actions = vec![];
+        let mut tree = stack(vec![text("a"), Field::boxed(1)]);
+
+        tree.focus_first(
+            FocusDirection::Next,
+            &mut RetainedMutableContext::new(&mut vec![]),
+        );
+        tree.replace(1, text("b"), &mut RetainedMutableContext::new(&mut actions));
+
+        assert_eq!(tree.focused(), None);
+        assert!(actions
+            .iter()
+            .any(|action| matches!(action, MutableContextAction::SetFocus(false))));
+    }
+
+    #[test]
+    fn releases_mouse_capture_of_removed_child() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tree = stack(vec![Field::boxed(0), Field::boxed(1)]);
+        let position = Position::new(0, 1);
+        let args = MouseButtonEventArgs::new(position, KeyModifiers::NONE, MouseButton::Left);
+
+        tree.draw(&mut Screen::new(Size::new(1, 2)), Size::new(1, 2));
+        tree.on_mouse_move(&MouseEventArgs::new(position, KeyModifiers::NONE), context);
+        tree.on_mouse_down(&args, context);
+
+        let removed = tree.remove(1, context).unwrap();
+
+        assert!(!field(Some(&*removed)).is_hovered);
+        assert!(matches!(
+            actions.last(),
+            Some(MutableContextAction::SetMouseCapture(false))
+        ));
+    }
+
+    #[test]
+    fn reconciles_children_by_keys() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut previous = stack(vec![]).with_keyed_children(vec![
+            ("a".into(), Field::boxed(1)),
+            ("b".into(), Field::boxed(2)),
+            ("c".into(), Field::boxed(3)),
+        ]);
+
+        previous.focus_first(FocusDirection::Previous, context);
+
+        let mut tree = stack(vec![]).with_keyed_children(vec![
+            ("c".into(), Field::boxed(0)),
+            ("d".into(), Field::boxed(0)),
+            ("a".into(), Field::boxed(0)),
+        ]);
+
+        tree.reconcile(&previous);
+
+        assert_eq!(states(&tree), [3, 0, 1]);
+        assert_eq!(tree.focused(), Some(0));
+    }
+
+    #[test]
+    fn reconciles_unkeyed_children_by_indices() {
+        let previous = stack(vec![Field::boxed(1), Field::boxed(2)]);
+        let mut tree = stack(vec![Field::boxed(0), Field::boxed(0), Field::boxed(0)]);
+
+        tree.push_keyed("a", Field::boxed(0));
+        tree.reconcile(&previous);
+
+        assert_eq!(states(&tree), [1, 2, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_duplicate_keys() {
+        let mut tree = stack(vec![]);
+
+        tree.push_keyed("a", Field::boxed(0));
+        tree.push_keyed("a", Field::boxed(1));
+    }
 }
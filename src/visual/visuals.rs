@@ -1,17 +1,31 @@
+mod bar_chart;
 mod border;
 mod button;
+mod canvas;
 mod hstack;
+mod line_chart;
 mod list;
+mod progress;
+mod sparkline;
+mod table;
+mod tabs;
 mod text_block;
 mod text_box;
 mod tree;
 mod vstack;
 
+pub use bar_chart::{Bar, BarChart, BarGroup};
+pub use canvas::{Canvas, Marker, Shape};
+pub use line_chart::{Axis, Dataset, GraphType, LineChart};
+pub use progress::{Gauge, Marquee, ProgressBar, Spinner, SpinnerFrames};
+pub use sparkline::Sparkline;
+pub use table::{Column, ColumnWidth, SortOrder, Table};
+pub use tabs::{Tab, Tabs};
 pub use text_block::TextBlock;
 
 use crate::{layout::VStackLayout, visual::TreeVisual};
 
-pub type VStack<'a, I> = TreeVisual<'a, VStackLayout, I>;
+pub type VStack<I> = TreeVisual<VStackLayout, I>;
 
 #[macro_export]
 macro_rules! vstack {
@@ -0,0 +1,408 @@
+use crate::{
+    buffer::{CellGrid, WriteBuffer},
+    input::VisualInput,
+    layout::{Alignment, Orientation},
+    style::{Attribute, Style, Styled},
+    visual::{Draw, Visual},
+    Size,
+};
+
+use super::{
+    progress::{EIGHTH_BLOCKS, FULL_BLOCK},
+    sparkline::{scaled_eighths, vertical_bar_symbol},
+};
+
+/// Single bar of the [`BarChart`].
+pub struct Bar {
+    value: u64,
+    label: Option<String>,
+    style: Option<Style>,
+}
+
+impl Bar {
+    pub fn new(value: u64) -> Self {
+        Self {
+            value,
+            label: None,
+            style: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Overrides the bar style of the chart for this bar.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+/// Bars of the [`BarChart`] which are drawn next to each other under a common label.
+pub struct BarGroup {
+    label: Option<String>,
+    bars: Vec<Bar>,
+}
+
+impl BarGroup {
+    pub fn new(bars: Vec<Bar>) -> Self {
+        Self { label: None, bars }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// Shows values as vertical or horizontal bars, optionally grouped.
+///
+/// Vertical bars show bar labels and group labels in rows under the bars,
+/// horizontal bars show them in a column on the left.
+pub struct BarChart {
+    groups: Vec<BarGroup>,
+    orientation: Orientation,
+    /// Thickness of a bar: columns for vertical bars, rows for horizontal ones.
+    bar_width: u16,
+    bar_gap: u16,
+    group_gap: u16,
+    /// Value of the longest possible bar, the maximum of the data by default.
+    max: Option<u64>,
+    /// Preferred height of the vertical chart, it takes all available height by default.
+    height: Option<u16>,
+
+    style: Style,
+    bar_style: Style,
+    value_style: Style,
+    label_style: Style,
+}
+
+impl BarChart {
+    pub fn new(groups: Vec<BarGroup>) -> Self {
+        Self {
+            groups,
+            orientation: Orientation::Vertical,
+            bar_width: 3,
+            bar_gap: 1,
+            group_gap: 2,
+            max: None,
+            height: None,
+            style: Style::default(),
+            bar_style: Style::default(),
+            value_style: Style::default().attributes(Attribute::Reverse.into()),
+            label_style: Style::default(),
+        }
+    }
+
+    /// Creates chart of ungrouped bars.
+    pub fn from_bars(bars: Vec<Bar>) -> Self {
+        Self::new(
+            bars.into_iter()
+                .map(|bar| BarGroup::new(vec![bar]))
+                .collect(),
+        )
+        .with_group_gap(1)
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_bar_width(mut self, width: u16) -> Self {
+        self.bar_width = width.max(1);
+        self
+    }
+
+    pub fn with_bar_gap(mut self, gap: u16) -> Self {
+        self.bar_gap = gap;
+        self
+    }
+
+    pub fn with_group_gap(mut self, gap: u16) -> Self {
+        self.group_gap = gap;
+        self
+    }
+
+    pub fn with_max(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn with_height(mut self, height: u16) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn with_bar_style(mut self, style: Style) -> Self {
+        self.bar_style = style;
+        self
+    }
+
+    pub fn with_value_style(mut self, style: Style) -> Self {
+        self.value_style = style;
+        self
+    }
+
+    pub fn with_label_style(mut self, style: Style) -> Self {
+        self.label_style = style;
+        self
+    }
+
+    pub fn groups(&self) -> &[BarGroup] {
+        &self.groups
+    }
+
+    pub fn set_groups(&mut self, groups: Vec<BarGroup>) {
+        self.groups = groups;
+    }
+
+    fn max(&self) -> u64 {
+        self.max
+            .unwrap_or_else(|| self.bars().map(|bar| bar.value).max().unwrap_or(0))
+            .max(1)
+    }
+
+    fn bars(&self) -> impl Iterator<Item = &Bar> {
+        self.groups.iter().flat_map(|group| group.bars.iter())
+    }
+
+    fn has_bar_labels(&self) -> bool {
+        self.bars().any(|bar| bar.label.is_some())
+    }
+
+    fn has_group_labels(&self) -> bool {
+        self.groups.iter().any(|group| group.label.is_some())
+    }
+
+    /// Length of the bars and gaps along the axis on which bars are placed.
+    fn extent(&self) -> u16 {
+        let mut extent: u16 = 0;
+
+        for (index, group) in self.groups.iter().enumerate() {
+            if index > 0 {
+                extent = extent.saturating_add(self.group_gap);
+            }
+
+            let bars = group.bars.len() as u16;
+
+            extent = extent
+                .saturating_add(bars.saturating_mul(self.bar_width))
+                .saturating_add(bars.saturating_sub(1).saturating_mul(self.bar_gap));
+        }
+
+        extent
+    }
+
+    fn bar_style(&self, bar: &Bar) -> Style {
+        bar.style.unwrap_or(self.bar_style)
+    }
+
+    fn draw_vertical(&self, grid: &mut CellGrid) {
+        let size = grid.size();
+        let label_rows = u16::from(self.has_bar_labels()) + u16::from(self.has_group_labels());
+        let chart_height = size.height.saturating_sub(label_rows);
+        let total_eighths = u64::from(chart_height) * 8;
+        let max = self.max();
+        let mut x: u16 = 0;
+
+        for (group_index, group) in self.groups.iter().enumerate() {
+            if group_index > 0 {
+                x = x.saturating_add(self.group_gap);
+            }
+
+            let group_start = x;
+
+            for (bar_index, bar) in group.bars.iter().enumerate() {
+                if bar_index > 0 {
+                    x = x.saturating_add(self.bar_gap);
+                }
+
+                let style = self.bar_style(bar);
+                let eighths = scaled_eighths(bar.value, max, total_eighths);
+
+                for row in 0..chart_height {
+                    let symbol = vertical_bar_symbol(eighths, u64::from(row));
+
+                    for column in 0..self.bar_width {
+                        grid.set(
+                            x.saturating_add(column),
+                            chart_height - row - 1,
+                            symbol,
+                            style,
+                        );
+                    }
+                }
+
+                // the value is shown in the bottom cell of the bar, when it fits
+                let value = bar.value.to_string();
+                let value_width = value.chars().count() as u16;
+
+                if chart_height > 0 && value_width <= self.bar_width && eighths >= 8 {
+                    let offset = Alignment::Center.offset(value_width, self.bar_width);
+
+                    grid.set_text(
+                        x.saturating_add(offset),
+                        chart_height - 1,
+                        &value,
+                        self.value_style,
+                    );
+                }
+
+                if let Some(label) = &bar.label {
+                    let label: String = label.chars().take(self.bar_width as usize).collect();
+                    let offset =
+                        Alignment::Center.offset(label.chars().count() as u16, self.bar_width);
+
+                    grid.set_text(
+                        x.saturating_add(offset),
+                        chart_height,
+                        &label,
+                        self.label_style,
+                    );
+                }
+
+                x = x.saturating_add(self.bar_width);
+            }
+
+            if let Some(label) = &group.label {
+                let width = x - group_start;
+                let label: String = label.chars().take(width as usize).collect();
+                let offset = Alignment::Center.offset(label.chars().count() as u16, width);
+
+                grid.set_text(
+                    group_start + offset,
+                    size.height.saturating_sub(1),
+                    &label,
+                    self.label_style,
+                );
+            }
+        }
+    }
+
+    fn draw_horizontal(&self, grid: &mut CellGrid) {
+        let size = grid.size();
+        let max = self.max();
+
+        let label_width = self
+            .groups
+            .iter()
+            .flat_map(|group| {
+                group.bars.iter().enumerate().map(|(index, bar)| {
+                    Self::horizontal_label(group, index, bar)
+                        .map_or(0, |label| label.chars().count())
+                })
+            })
+            .max()
+            .map_or(0, |width| width + 1) as u16;
+
+        let value_width = self
+            .bars()
+            .map(|bar| bar.value.to_string().len() + 1)
+            .max()
+            .unwrap_or(0) as u16;
+
+        let chart_width = size
+            .width
+            .saturating_sub(label_width.saturating_add(value_width));
+        let total_eighths = u64::from(chart_width) * 8;
+        let mut y: u16 = 0;
+
+        for (group_index, group) in self.groups.iter().enumerate() {
+            if group_index > 0 {
+                y = y.saturating_add(self.group_gap);
+            }
+
+            for (bar_index, bar) in group.bars.iter().enumerate() {
+                if bar_index > 0 {
+                    y = y.saturating_add(self.bar_gap);
+                }
+
+                if let Some(label) = Self::horizontal_label(group, bar_index, bar) {
+                    grid.set_text(0, y, label, self.label_style);
+                }
+
+                let style = self.bar_style(bar);
+                let eighths = scaled_eighths(bar.value, max, total_eighths);
+                let full = (eighths / 8) as u16;
+                let remainder = (eighths % 8) as usize;
+
+                for row in y..y.saturating_add(self.bar_width) {
+                    for column in 0..full {
+                        grid.set(label_width + column, row, FULL_BLOCK, style);
+                    }
+
+                    if remainder > 0 {
+                        grid.set(label_width + full, row, EIGHTH_BLOCKS[remainder - 1], style);
+                    }
+                }
+
+                let value_x = label_width
+                    .saturating_add(full)
+                    .saturating_add(u16::from(remainder > 0) + 1);
+
+                grid.set_text(value_x, y, &bar.value.to_string(), self.style);
+
+                y = y.saturating_add(self.bar_width);
+            }
+        }
+    }
+
+    /// Label of the horizontal bar is the bar label or, for the first bar, the group label.
+    fn horizontal_label<'a>(group: &'a BarGroup, index: usize, bar: &'a Bar) -> Option<&'a str> {
+        match (&bar.label, &group.label) {
+            (Some(label), _) => Some(label),
+            (None, Some(label)) if index == 0 => Some(label),
+            _ => None,
+        }
+    }
+}
+
+impl Styled for BarChart {
+    type Item = BarChart;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for BarChart {}
+
+impl Draw for BarChart {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+        let mut grid = CellGrid::new(size, self.style);
+
+        match self.orientation {
+            Orientation::Vertical => self.draw_vertical(&mut grid),
+            Orientation::Horizontal => self.draw_horizontal(&mut grid),
+        }
+
+        grid.write_to(buffer).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        match self.orientation {
+            Orientation::Vertical => {
+                Size::new(self.extent(), self.height.unwrap_or(constraints.height))
+            }
+            Orientation::Horizontal => Size::new(constraints.width, self.extent()),
+        }
+        .clip(constraints)
+    }
+}
+
+impl Visual for BarChart {}
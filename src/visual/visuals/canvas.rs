@@ -0,0 +1,469 @@
+use crate::{
+    buffer::{CellGrid, WriteBuffer},
+    input::VisualInput,
+    style::{Color, Style, Styled},
+    visual::{Draw, Visual},
+    Size,
+};
+
+/// Symbols used to rasterize shapes, which defines the amount of points in a cell.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Marker {
+    /// Braille symbols with 2×4 points per cell.
+    #[default]
+    Braille,
+    /// Quadrant blocks with 2×2 points per cell.
+    Quadrant,
+    /// Half blocks with 1×2 points per cell.
+    HalfBlock,
+}
+
+impl Marker {
+    /// Amount of points in a cell horizontally and vertically.
+    pub fn resolution(self) -> (u16, u16) {
+        match self {
+            Marker::Braille => (2, 4),
+            Marker::Quadrant => (2, 2),
+            Marker::HalfBlock => (1, 2),
+        }
+    }
+
+    /// Returns bit of the point in the cell.
+    fn bit(self, x: usize, y: usize) -> u8 {
+        match self {
+            // the first three rows are numbered by columns, the last row is added later
+            Marker::Braille => [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]][y][x],
+            Marker::Quadrant => 1 << (y * 2 + x),
+            Marker::HalfBlock => 1 << y,
+        }
+    }
+
+    fn symbol(self, bits: u8) -> char {
+        const QUADRANTS: [char; 16] = [
+            ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+        ];
+        const HALF_BLOCKS: [char; 4] = [' ', '▀', '▄', '█'];
+
+        match self {
+            Marker::Braille => char::from_u32(0x2800 + u32::from(bits)).unwrap_or(' '),
+            Marker::Quadrant => QUADRANTS[bits as usize & 0x0f],
+            Marker::HalfBlock => HALF_BLOCKS[bits as usize & 0x03],
+        }
+    }
+}
+
+/// Grid of cells which is drawn by points, the amount of which in a cell
+/// depends on the [`Marker`].
+///
+/// Each cell has the style of the last point set in it.
+pub(crate) struct Raster {
+    size: Size,
+    marker: Marker,
+    points: Vec<u8>,
+    styles: Vec<Option<Style>>,
+}
+
+impl Raster {
+    pub fn new(size: Size, marker: Marker) -> Self {
+        let cells = size.width as usize * size.height as usize;
+
+        Self {
+            size,
+            marker,
+            points: vec![0; cells],
+            styles: vec![None; cells],
+        }
+    }
+
+    /// Width and height in points.
+    pub fn resolution(&self) -> (f64, f64) {
+        let (x, y) = self.marker.resolution();
+
+        (
+            f64::from(self.size.width) * f64::from(x),
+            f64::from(self.size.height) * f64::from(y),
+        )
+    }
+
+    /// Sets the point, points outside the raster are ignored.
+    pub fn set_point(&mut self, x: i64, y: i64, style: Style) {
+        let (width, height) = self.resolution();
+
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            return;
+        }
+
+        let (cell_width, cell_height) = self.marker.resolution();
+        let (cell_width, cell_height) = (i64::from(cell_width), i64::from(cell_height));
+        let index =
+            (y / cell_height) as usize * self.size.width as usize + (x / cell_width) as usize;
+
+        self.points[index] |= self
+            .marker
+            .bit((x % cell_width) as usize, (y % cell_height) as usize);
+        self.styles[index] = Some(style);
+    }
+
+    pub fn draw_line(&mut self, from: (f64, f64), to: (f64, f64), style: Style) {
+        let (width, height) = self.resolution();
+
+        let Some(((x0, y0), (x1, y1))) = clip_line(from, to, width - 1.0, height - 1.0) else {
+            return;
+        };
+
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0);
+
+        for step in 0..=steps as i64 {
+            let t = step as f64 / steps;
+
+            self.set_point(
+                (x0 + (x1 - x0) * t).round() as i64,
+                (y0 + (y1 - y0) * t).round() as i64,
+                style,
+            );
+        }
+    }
+
+    /// Copies cells with at least one point into the grid at the given offset.
+    pub fn write_to(&self, grid: &mut CellGrid, x: u16, y: u16) {
+        for (index, (points, style)) in self.points.iter().zip(self.styles.iter()).enumerate() {
+            if let Some(style) = style {
+                let column = (index % self.size.width as usize) as u16;
+                let row = (index / self.size.width as usize) as u16;
+
+                grid.set(x + column, y + row, self.marker.symbol(*points), *style);
+            }
+        }
+    }
+}
+
+/// Clips the line by the rectangle from zero to the given maximums,
+/// returns `None` if the line is outside of the rectangle.
+fn clip_line(
+    (x0, y0): (f64, f64),
+    (x1, y1): (f64, f64),
+    max_x: f64,
+    max_y: f64,
+) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+
+    for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some(((x0 + dx * t0, y0 + dy * t0), (x0 + dx * t1, y0 + dy * t1)))
+}
+
+/// Figure drawn on the [`Canvas`], coordinates are in the world coordinates of the canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Points {
+        coords: Vec<(f64, f64)>,
+        color: Color,
+    },
+    Line {
+        from: (f64, f64),
+        to: (f64, f64),
+        color: Color,
+    },
+    /// Outline of the rectangle with the given bottom left corner.
+    Rectangle {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: Color,
+    },
+    Circle {
+        x: f64,
+        y: f64,
+        radius: f64,
+        color: Color,
+    },
+    /// Closed outline through the given vertices.
+    Polygon {
+        vertices: Vec<(f64, f64)>,
+        color: Color,
+    },
+    /// Text which starts in the cell containing the given point.
+    Label {
+        x: f64,
+        y: f64,
+        text: String,
+        style: Style,
+    },
+}
+
+/// Surface for drawing shapes in world coordinates, which are mapped
+/// to the points of the [`Marker`] symbols.
+///
+/// The viewport is defined by bounds of the world coordinates, the y axis
+/// increases upwards.
+///
+/// # Examples
+///
+/// ```
+/// use termrs::{style::Color, visual::{Canvas, Marker}};
+///
+/// let mut canvas = Canvas::new([0.0, 100.0], [0.0, 50.0]).with_marker(Marker::Quadrant);
+///
+/// canvas.draw_line((0.0, 0.0), (100.0, 50.0), Color::Green);
+/// canvas.draw_circle(50.0, 25.0, 10.0, Color::Red);
+/// canvas.draw_label(40.0, 45.0, "center", Default::default());
+/// ```
+pub struct Canvas {
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    marker: Marker,
+    shapes: Vec<Shape>,
+    /// Preferred height, the canvas takes all available height by default.
+    height: Option<u16>,
+    style: Style,
+}
+
+impl Canvas {
+    pub fn new(x_bounds: [f64; 2], y_bounds: [f64; 2]) -> Self {
+        Self {
+            x_bounds,
+            y_bounds,
+            marker: Marker::Braille,
+            shapes: vec![],
+            height: None,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_marker(mut self, marker: Marker) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    pub fn with_height(mut self, height: u16) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Sets the world coordinates which are visible.
+    pub fn set_viewport(&mut self, x_bounds: [f64; 2], y_bounds: [f64; 2]) {
+        self.x_bounds = x_bounds;
+        self.y_bounds = y_bounds;
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    pub fn draw_shape(&mut self, shape: Shape) {
+        self.shapes.push(shape);
+    }
+
+    pub fn draw_point(&mut self, x: f64, y: f64, color: Color) {
+        self.draw_shape(Shape::Points {
+            coords: vec![(x, y)],
+            color,
+        });
+    }
+
+    pub fn draw_line(&mut self, from: (f64, f64), to: (f64, f64), color: Color) {
+        self.draw_shape(Shape::Line { from, to, color });
+    }
+
+    pub fn draw_rectangle(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        self.draw_shape(Shape::Rectangle {
+            x,
+            y,
+            width,
+            height,
+            color,
+        });
+    }
+
+    pub fn draw_circle(&mut self, x: f64, y: f64, radius: f64, color: Color) {
+        self.draw_shape(Shape::Circle {
+            x,
+            y,
+            radius,
+            color,
+        });
+    }
+
+    pub fn draw_polygon(&mut self, vertices: Vec<(f64, f64)>, color: Color) {
+        self.draw_shape(Shape::Polygon { vertices, color });
+    }
+
+    pub fn draw_label(&mut self, x: f64, y: f64, text: impl Into<String>, style: Style) {
+        self.draw_shape(Shape::Label {
+            x,
+            y,
+            text: text.into(),
+            style,
+        });
+    }
+
+    /// Removes all shapes.
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// Maps world coordinates to the ratio of the viewport, where (0, 0) is the top left corner.
+    fn to_ratio(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let [left, right] = self.x_bounds;
+        let [bottom, top] = self.y_bounds;
+
+        let ratio = |value: f64, min: f64, max: f64| {
+            if max == min {
+                0.0
+            } else {
+                (value - min) / (max - min)
+            }
+        };
+
+        (ratio(x, left, right), 1.0 - ratio(y, bottom, top))
+    }
+
+    fn rasterize(&self, shape: &Shape, raster: &mut Raster) {
+        let (width, height) = raster.resolution();
+        let to_points = |point: (f64, f64)| {
+            let (x, y) = self.to_ratio(point);
+
+            (x * (width - 1.0), y * (height - 1.0))
+        };
+
+        let style = |color: &Color| self.style.foreground(*color);
+        let polyline = |raster: &mut Raster, vertices: &[(f64, f64)], style: Style| {
+            for (index, vertex) in vertices.iter().enumerate() {
+                let next = vertices[(index + 1) % vertices.len()];
+
+                raster.draw_line(to_points(*vertex), to_points(next), style);
+            }
+        };
+
+        match shape {
+            Shape::Points { coords, color } => {
+                for point in coords.iter() {
+                    let (x, y) = to_points(*point);
+
+                    raster.set_point(x.round() as i64, y.round() as i64, style(color));
+                }
+            }
+            Shape::Line { from, to, color } => {
+                raster.draw_line(to_points(*from), to_points(*to), style(color));
+            }
+            Shape::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => {
+                let vertices = [
+                    (*x, *y),
+                    (x + width, *y),
+                    (x + width, y + height),
+                    (*x, y + height),
+                ];
+
+                polyline(raster, &vertices, style(color));
+            }
+            Shape::Circle {
+                x,
+                y,
+                radius,
+                color,
+            } => {
+                // approximate by a polygon with sides about one point long
+                let (rx, _) = to_points((x + radius, *y));
+                let (cx, cy) = to_points((*x, *y));
+                let (_, ty) = to_points((*x, y + radius));
+                let circumference = std::f64::consts::TAU * (rx - cx).abs().max((ty - cy).abs());
+                let sides = (circumference.ceil() as usize).clamp(8, 4096);
+
+                let vertices: Vec<(f64, f64)> = (0..sides)
+                    .map(|side| {
+                        let angle = std::f64::consts::TAU * side as f64 / sides as f64;
+
+                        (x + radius * angle.cos(), y + radius * angle.sin())
+                    })
+                    .collect();
+
+                polyline(raster, &vertices, style(color));
+            }
+            Shape::Polygon { vertices, color } => {
+                if !vertices.is_empty() {
+                    polyline(raster, vertices, style(color));
+                }
+            }
+            Shape::Label { .. } => {}
+        }
+    }
+}
+
+impl Styled for Canvas {
+    type Item = Canvas;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Canvas {}
+
+impl Draw for Canvas {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+        let mut grid = CellGrid::new(size, self.style);
+        let mut raster = Raster::new(size, self.marker);
+
+        for shape in self.shapes.iter() {
+            self.rasterize(shape, &mut raster);
+        }
+
+        raster.write_to(&mut grid, 0, 0);
+
+        // labels are drawn over the shapes
+        for shape in self.shapes.iter() {
+            if let Shape::Label { x, y, text, style } = shape {
+                let (x, y) = self.to_ratio((*x, *y));
+
+                if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) {
+                    let column = (x * f64::from(size.width.saturating_sub(1))).round() as u16;
+                    let row = (y * f64::from(size.height.saturating_sub(1))).round() as u16;
+
+                    grid.set_text(column, row, text, *style);
+                }
+            }
+        }
+
+        grid.write_to(buffer).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        Size::new(constraints.width, self.height.unwrap_or(constraints.height)).clip(constraints)
+    }
+}
+
+impl Visual for Canvas {}
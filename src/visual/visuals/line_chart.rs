@@ -0,0 +1,402 @@
+use crate::{
+    buffer::{CellGrid, WriteBuffer},
+    input::VisualInput,
+    style::{Style, Styled},
+    visual::{Draw, Visual},
+    Size,
+};
+
+use super::canvas::{Marker, Raster};
+
+/// How points of the [`Dataset`] are drawn.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum GraphType {
+    /// Consecutive points are connected with lines.
+    #[default]
+    Line,
+    /// Only points themselves are drawn.
+    Scatter,
+}
+
+/// Named series of points drawn by the [`LineChart`].
+pub struct Dataset {
+    name: String,
+    data: Vec<(f64, f64)>,
+    graph_type: GraphType,
+    style: Style,
+}
+
+impl Dataset {
+    pub fn new(name: impl Into<String>, data: Vec<(f64, f64)>) -> Self {
+        Self {
+            name: name.into(),
+            data,
+            graph_type: GraphType::Line,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_graph_type(mut self, graph_type: GraphType) -> Self {
+        self.graph_type = graph_type;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data(&self) -> &[(f64, f64)] {
+        &self.data
+    }
+
+    pub fn set_data(&mut self, data: Vec<(f64, f64)>) {
+        self.data = data;
+    }
+}
+
+impl Styled for Dataset {
+    type Item = Dataset;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+/// Axis of the [`LineChart`]: the range of shown values and labels
+/// which are spread evenly along the axis.
+pub struct Axis {
+    title: Option<String>,
+    bounds: [f64; 2],
+    labels: Vec<String>,
+    style: Style,
+}
+
+impl Axis {
+    pub fn new(bounds: [f64; 2]) -> Self {
+        Self {
+            title: None,
+            bounds,
+            labels: vec![],
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: Vec<String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Creates labels for the given amount of evenly spread values of the bounds.
+    pub fn with_tick_labels(self, count: usize, precision: usize) -> Self {
+        let [min, max] = self.bounds;
+        let labels = (0..count)
+            .map(|index| {
+                let ratio = if count > 1 {
+                    index as f64 / (count - 1) as f64
+                } else {
+                    0.0
+                };
+
+                format!("{:.*}", precision, min + (max - min) * ratio)
+            })
+            .collect();
+
+        self.with_labels(labels)
+    }
+
+    pub fn bounds(&self) -> [f64; 2] {
+        self.bounds
+    }
+
+    pub fn set_bounds(&mut self, bounds: [f64; 2]) {
+        self.bounds = bounds;
+    }
+
+    /// Maps the value to the range from 0 to 1 of the axis.
+    fn ratio(&self, value: f64) -> f64 {
+        let [min, max] = self.bounds;
+
+        if max == min {
+            0.0
+        } else {
+            (value - min) / (max - min)
+        }
+    }
+}
+
+impl Styled for Axis {
+    type Item = Axis;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+/// Plots datasets as lines or scattered points with axes, tick labels and an optional legend.
+///
+/// Points are drawn by braille symbols by default, which gives 2×4 points per cell.
+pub struct LineChart {
+    datasets: Vec<Dataset>,
+    x_axis: Axis,
+    y_axis: Axis,
+    marker: Marker,
+    show_legend: bool,
+    /// Preferred height, the chart takes all available height by default.
+    height: Option<u16>,
+    style: Style,
+}
+
+impl LineChart {
+    pub fn new(datasets: Vec<Dataset>, x_axis: Axis, y_axis: Axis) -> Self {
+        Self {
+            datasets,
+            x_axis,
+            y_axis,
+            marker: Marker::Braille,
+            show_legend: true,
+            height: None,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_marker(mut self, marker: Marker) -> Self {
+        self.marker = marker;
+        self
+    }
+
+    pub fn with_legend(mut self, show_legend: bool) -> Self {
+        self.show_legend = show_legend;
+        self
+    }
+
+    pub fn with_height(mut self, height: u16) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn datasets(&self) -> &[Dataset] {
+        &self.datasets
+    }
+
+    pub fn datasets_mut(&mut self) -> &mut Vec<Dataset> {
+        &mut self.datasets
+    }
+
+    pub fn x_axis_mut(&mut self) -> &mut Axis {
+        &mut self.x_axis
+    }
+
+    pub fn y_axis_mut(&mut self) -> &mut Axis {
+        &mut self.y_axis
+    }
+
+    fn draw_legend(&self, grid: &mut CellGrid, right: u16, top: u16) {
+        let width = self
+            .datasets
+            .iter()
+            .map(|dataset| dataset.name.chars().count() + 2)
+            .max()
+            .unwrap_or(0) as u16;
+
+        let x = right.saturating_sub(width);
+
+        for (index, dataset) in self.datasets.iter().enumerate() {
+            let marker = match dataset.graph_type {
+                GraphType::Line => '─',
+                GraphType::Scatter => '•',
+            };
+
+            grid.set(x, top + index as u16, marker, dataset.style);
+            grid.set_text(x + 2, top + index as u16, &dataset.name, self.style);
+        }
+    }
+}
+
+impl Styled for LineChart {
+    type Item = LineChart;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for LineChart {}
+
+impl Draw for LineChart {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+        let mut grid = CellGrid::new(size, self.style);
+
+        let top = u16::from(self.y_axis.title.is_some());
+        let bottom_rows =
+            u16::from(!self.x_axis.labels.is_empty()) + u16::from(self.x_axis.title.is_some());
+
+        let y_labels_width = self
+            .y_axis
+            .labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0) as u16;
+
+        // position of the axes lines
+        let axis_x = y_labels_width;
+        let axis_y = size.height.saturating_sub(bottom_rows + 1);
+
+        if size.height == 0 || axis_y < top || axis_x >= size.width {
+            grid.write_to(buffer).expect("Cannot write to buffer");
+            return size;
+        }
+
+        let plot_width = size.width - axis_x - 1;
+        let plot_height = axis_y - top;
+
+        // axes
+        for y in top..axis_y {
+            grid.set(axis_x, y, '│', self.y_axis.style);
+        }
+
+        for x in axis_x + 1..size.width {
+            grid.set(x, axis_y, '─', self.x_axis.style);
+        }
+
+        grid.set(axis_x, axis_y, '└', self.x_axis.style);
+
+        if let Some(title) = &self.y_axis.title {
+            grid.set_text(0, 0, title, self.y_axis.style);
+        }
+
+        // y labels from the bottom to the top
+        let y_labels = &self.y_axis.labels;
+
+        for (index, label) in y_labels.iter().enumerate().filter(|_| plot_height > 0) {
+            let offset = spread(index, y_labels.len(), plot_height.saturating_sub(1));
+            let x = y_labels_width - label.chars().count() as u16;
+
+            grid.set_text(x, axis_y - 1 - offset, label, self.y_axis.style);
+        }
+
+        // x labels, the first is aligned to the left and the last to the right
+        let x_labels = &self.x_axis.labels;
+
+        for (index, label) in x_labels.iter().enumerate() {
+            let tick = axis_x + 1 + spread(index, x_labels.len(), plot_width.saturating_sub(1));
+            let label_width = label.chars().count() as u16;
+
+            let x = if index == 0 {
+                tick
+            } else if index == x_labels.len() - 1 {
+                size.width.saturating_sub(label_width)
+            } else {
+                tick.saturating_sub(label_width / 2)
+            };
+
+            grid.set_text(x, axis_y + 1, label, self.x_axis.style);
+        }
+
+        if let Some(title) = &self.x_axis.title {
+            let x = size.width.saturating_sub(title.chars().count() as u16);
+
+            grid.set_text(x, size.height - 1, title, self.x_axis.style);
+        }
+
+        // data
+        let mut raster = Raster::new(Size::new(plot_width, plot_height), self.marker);
+        let (points_width, points_height) = raster.resolution();
+
+        let to_points = |(x, y): (f64, f64)| {
+            (
+                self.x_axis.ratio(x) * (points_width - 1.0),
+                (1.0 - self.y_axis.ratio(y)) * (points_height - 1.0),
+            )
+        };
+
+        for dataset in self.datasets.iter() {
+            match dataset.graph_type {
+                GraphType::Line => {
+                    for points in dataset.data.windows(2) {
+                        raster.draw_line(to_points(points[0]), to_points(points[1]), dataset.style);
+                    }
+
+                    if let [point] = dataset.data[..] {
+                        let (x, y) = to_points(point);
+
+                        raster.set_point(x.round() as i64, y.round() as i64, dataset.style);
+                    }
+                }
+                GraphType::Scatter => {
+                    for point in dataset.data.iter() {
+                        let (x, y) = to_points(*point);
+
+                        raster.set_point(x.round() as i64, y.round() as i64, dataset.style);
+                    }
+                }
+            }
+        }
+
+        raster.write_to(&mut grid, axis_x + 1, top);
+
+        if self.show_legend && !self.datasets.is_empty() {
+            self.draw_legend(&mut grid, size.width, top);
+        }
+
+        grid.write_to(buffer).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        Size::new(constraints.width, self.height.unwrap_or(constraints.height)).clip(constraints)
+    }
+}
+
+impl Visual for LineChart {}
+
+/// Returns offset of the item with the given index when
+/// the given amount of items is spread evenly over the length.
+fn spread(index: usize, count: usize, length: u16) -> u16 {
+    if count <= 1 {
+        0
+    } else {
+        (index * length as usize / (count - 1)) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::Terminal;
+
+    #[test]
+    fn draw_with_zero_height_does_not_overflow() {
+        let chart = LineChart::new(
+            vec![Dataset::new("data", vec![(0.0, 0.0), (1.0, 1.0)])],
+            Axis::new([0.0, 1.0]).with_title("x"),
+            Axis::new([0.0, 1.0]),
+        );
+
+        let size = chart.draw(&mut Terminal::new(Vec::new()), Size::new(20, 0));
+
+        assert_eq!(size, Size::new(20, 0));
+    }
+}
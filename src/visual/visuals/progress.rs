@@ -0,0 +1,490 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    animation::{Animation, Easing, Tween},
+    buffer::{write_cells, WriteBuffer},
+    input::VisualInput,
+    style::{Attribute, Style, Styled},
+    visual::{Draw, Visual},
+    Position, Size,
+};
+
+/// Blocks filled by one to seven eighths from the left.
+pub(crate) const EIGHTH_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+pub(crate) const FULL_BLOCK: char = '█';
+
+/// Horizontal bar which fills the available width proportionally to the progress,
+/// using eighth-block characters for sub-cell precision.
+pub struct ProgressBar {
+    /// Progress in the range from 0 to 1.
+    ratio: f64,
+    /// Transition of the drawn progress to the ratio.
+    transition: Option<Tween<f64>>,
+    style: Style,
+}
+
+impl ProgressBar {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: clamp_ratio(ratio),
+            transition: None,
+            style: Style::default(),
+        }
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Sets progress, clamping it to the range from 0 to 1.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = clamp_ratio(ratio);
+        self.transition = None;
+    }
+
+    /// Fills the bar up to the progress smoothly during the given time.
+    pub fn animate_ratio(&mut self, ratio: f64, duration: Duration) {
+        let from = drawn_ratio(&self.transition, self.ratio);
+
+        self.ratio = clamp_ratio(ratio);
+        self.transition = Some(transition(from, self.ratio, duration));
+    }
+}
+
+impl Styled for ProgressBar {
+    type Item = ProgressBar;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for ProgressBar {}
+
+impl Draw for ProgressBar {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+
+        if size.height == 0 {
+            return size;
+        }
+
+        let width = size.width as usize;
+        let ratio = drawn_ratio(&self.transition, self.ratio);
+        let eighths = (ratio * (width * 8) as f64).round() as usize;
+        let mut cells = Vec::with_capacity(width);
+
+        cells.extend(std::iter::repeat_n((FULL_BLOCK, self.style), eighths / 8));
+
+        let remainder = eighths % 8;
+
+        if remainder > 0 {
+            cells.push((EIGHTH_BLOCKS[remainder - 1], self.style));
+        }
+
+        cells.resize(width, (' ', self.style));
+
+        write_cells(buffer, Position::default(), &cells).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        Size::new(constraints.width, 1).clip(constraints)
+    }
+
+    fn is_animating(&self) -> bool {
+        self.transition.as_ref().is_some_and(Animation::is_running)
+    }
+}
+
+impl Visual for ProgressBar {}
+
+/// Horizontal bar with a label drawn over it, which is a percentage by default.
+///
+/// The filled part uses the filled style, the rest uses the style of the gauge.
+pub struct Gauge {
+    /// Progress in the range from 0 to 1.
+    ratio: f64,
+    /// Transition of the drawn progress to the ratio.
+    transition: Option<Tween<f64>>,
+    label: Option<String>,
+    style: Style,
+    filled_style: Style,
+}
+
+impl Gauge {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: clamp_ratio(ratio),
+            transition: None,
+            label: None,
+            style: Style::default(),
+            filled_style: Style::default().attributes(Attribute::Reverse.into()),
+        }
+    }
+
+    /// Replaces the percentage with the given label.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_filled_style(mut self, style: Style) -> Self {
+        self.filled_style = style;
+        self
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Sets progress, clamping it to the range from 0 to 1.
+    pub fn set_ratio(&mut self, ratio: f64) {
+        self.ratio = clamp_ratio(ratio);
+        self.transition = None;
+    }
+
+    /// Fills the gauge up to the progress smoothly during the given time,
+    /// the percentage follows the drawn progress.
+    pub fn animate_ratio(&mut self, ratio: f64, duration: Duration) {
+        let from = drawn_ratio(&self.transition, self.ratio);
+
+        self.ratio = clamp_ratio(ratio);
+        self.transition = Some(transition(from, self.ratio, duration));
+    }
+
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn label(&self, ratio: f64) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => format!("{}%", (ratio * 100.0).round()),
+        }
+    }
+}
+
+impl Styled for Gauge {
+    type Item = Gauge;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Gauge {}
+
+impl Draw for Gauge {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+
+        if size.height == 0 {
+            return size;
+        }
+
+        let width = size.width as usize;
+        let ratio = drawn_ratio(&self.transition, self.ratio);
+        let filled = (ratio * width as f64).round() as usize;
+        let label: Vec<char> = self.label(ratio).chars().collect();
+        let label_width = label.len().min(width);
+        let label_start = (width - label_width) / 2;
+
+        let cells: Vec<(char, Style)> = (0..width)
+            .map(|x| {
+                let style = if x < filled {
+                    self.filled_style
+                } else {
+                    self.style
+                };
+
+                let symbol = if x >= label_start && x < label_start + label_width {
+                    label[x - label_start]
+                } else {
+                    ' '
+                };
+
+                (symbol, style)
+            })
+            .collect();
+
+        write_cells(buffer, Position::default(), &cells).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        Size::new(constraints.width, 1).clip(constraints)
+    }
+
+    fn is_animating(&self) -> bool {
+        self.transition.as_ref().is_some_and(Animation::is_running)
+    }
+}
+
+impl Visual for Gauge {}
+
+/// Indeterminate progress bar, a segment which bounces between the edges
+/// of the bar, moving by one cell every step interval.
+pub struct Marquee {
+    segment_width: u16,
+    started: Instant,
+    step_interval: Duration,
+    style: Style,
+}
+
+impl Marquee {
+    pub const DEFAULT_STEP_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new() -> Self {
+        Self {
+            segment_width: 4,
+            started: Instant::now(),
+            step_interval: Self::DEFAULT_STEP_INTERVAL,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_segment_width(mut self, width: u16) -> Self {
+        self.segment_width = width.max(1);
+        self
+    }
+
+    /// Sets time the segment takes to move by one cell.
+    pub fn with_step_interval(mut self, step_interval: Duration) -> Self {
+        self.step_interval = step_interval;
+        self
+    }
+
+    fn segment_start(&self, width: u16) -> usize {
+        let track = width.saturating_sub(self.segment_width) as usize;
+
+        if track == 0 {
+            return 0;
+        }
+
+        // offset of the segment in cells, counted along the way there and back
+        let step = elapsed_steps(self.started, self.step_interval) % (track * 2);
+
+        if step <= track {
+            step
+        } else {
+            track * 2 - step
+        }
+    }
+}
+
+impl Default for Marquee {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Styled for Marquee {
+    type Item = Marquee;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Marquee {}
+
+impl Draw for Marquee {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+
+        if size.height == 0 {
+            return size;
+        }
+
+        let width = size.width as usize;
+        let start = self.segment_start(size.width);
+        let end = (start + self.segment_width as usize).min(width);
+
+        let cells: Vec<(char, Style)> = (0..width)
+            .map(|x| {
+                let symbol = if x >= start && x < end {
+                    FULL_BLOCK
+                } else {
+                    ' '
+                };
+
+                (symbol, self.style)
+            })
+            .collect();
+
+        write_cells(buffer, Position::default(), &cells).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        Size::new(constraints.width, 1).clip(constraints)
+    }
+
+    fn is_animating(&self) -> bool {
+        true
+    }
+}
+
+impl Visual for Marquee {}
+
+/// Set of frames shown by the [`Spinner`] one after another.
+#[derive(Debug, Clone, Copy)]
+pub enum SpinnerFrames {
+    /// `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`
+    Dots,
+    /// `-\|/`
+    Line,
+    /// `◜◠◝◞◡◟`
+    Arc,
+    /// `▖▘▝▗`
+    Quadrants,
+    Custom(&'static [&'static str]),
+}
+
+impl SpinnerFrames {
+    pub fn frames(self) -> &'static [&'static str] {
+        match self {
+            SpinnerFrames::Dots => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            SpinnerFrames::Line => &["-", "\\", "|", "/"],
+            SpinnerFrames::Arc => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            SpinnerFrames::Quadrants => &["▖", "▘", "▝", "▗"],
+            SpinnerFrames::Custom(frames) => frames,
+        }
+    }
+}
+
+/// Shows frames of the frame set one after another, optionally followed by a label.
+pub struct Spinner {
+    frames: SpinnerFrames,
+    started: Instant,
+    frame_interval: Duration,
+    label: Option<String>,
+    style: Style,
+}
+
+impl Spinner {
+    pub const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(frames: SpinnerFrames) -> Self {
+        Self {
+            frames,
+            started: Instant::now(),
+            frame_interval: Self::DEFAULT_FRAME_INTERVAL,
+            label: None,
+            style: Style::default(),
+        }
+    }
+
+    /// Sets time each frame is shown.
+    pub fn with_frame_interval(mut self, frame_interval: Duration) -> Self {
+        self.frame_interval = frame_interval;
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn set_label(&mut self, label: Option<String>) {
+        self.label = label;
+    }
+
+    fn text(&self) -> String {
+        let frames = self.frames.frames();
+        let frame = elapsed_steps(self.started, self.frame_interval) % frames.len().max(1);
+        let frame = frames.get(frame).unwrap_or(&"");
+
+        match &self.label {
+            Some(label) => format!("{frame} {label}"),
+            None => frame.to_string(),
+        }
+    }
+}
+
+impl Styled for Spinner {
+    type Item = Spinner;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Spinner {}
+
+impl Draw for Spinner {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+
+        if size.height == 0 {
+            return size;
+        }
+
+        let text: String = self.text().chars().take(size.width as usize).collect();
+
+        buffer
+            .write_symbols(Position::default(), &text, self.style)
+            .expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        let width = self.text().chars().count().min(u16::MAX as usize) as u16;
+
+        Size::new(width, 1).clip(constraints)
+    }
+
+    fn is_animating(&self) -> bool {
+        true
+    }
+}
+
+impl Visual for Spinner {}
+
+/// Returns number of intervals elapsed since the start.
+fn elapsed_steps(started: Instant, interval: Duration) -> usize {
+    (started.elapsed().as_nanos() / interval.as_nanos().max(1)) as usize
+}
+
+fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        0.0
+    } else {
+        ratio.clamp(0.0, 1.0)
+    }
+}
+
+/// Returns progress which is drawn during the transition to the ratio.
+fn drawn_ratio(transition: &Option<Tween<f64>>, ratio: f64) -> f64 {
+    transition.as_ref().map_or(ratio, Animation::value)
+}
+
+/// Creates transition from the drawn progress, so a running transition continues smoothly.
+fn transition(from: f64, ratio: f64, duration: Duration) -> Tween<f64> {
+    Tween::new(from, ratio, duration).with_easing(Easing::EaseOut)
+}
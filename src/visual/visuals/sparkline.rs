@@ -0,0 +1,141 @@
+use crate::{
+    buffer::{CellGrid, WriteBuffer},
+    input::VisualInput,
+    style::{Style, Styled},
+    visual::{Draw, Visual},
+    Size,
+};
+
+/// Blocks filled by one to eight eighths from the bottom.
+pub(crate) const VERTICAL_EIGHTHS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Returns symbol of the cell in the given row counted from the bottom
+/// of a vertical bar which is the given amount of eighths high.
+pub(crate) fn vertical_bar_symbol(eighths: u64, row: u64) -> char {
+    let filled = eighths.saturating_sub(row * 8).min(8);
+
+    match filled {
+        0 => ' ',
+        filled => VERTICAL_EIGHTHS[filled as usize - 1],
+    }
+}
+
+/// Returns amount of eighths of the total which the value takes relative to the max,
+/// the max should not be zero.
+pub(crate) fn scaled_eighths(value: u64, max: u64, total_eighths: u64) -> u64 {
+    // the product does not fit in u64 for large values
+    (u128::from(value.min(max)) * u128::from(total_eighths) / u128::from(max)) as u64
+}
+
+/// Compact chart which shows each value as a column of block characters.
+///
+/// When there are more values than columns, the latest values are shown.
+pub struct Sparkline {
+    data: Vec<u64>,
+    /// Value which fills the whole height, the maximum of the data by default.
+    max: Option<u64>,
+    height: u16,
+    style: Style,
+}
+
+impl Sparkline {
+    pub fn new(data: Vec<u64>) -> Self {
+        Self {
+            data,
+            max: None,
+            height: 1,
+            style: Style::default(),
+        }
+    }
+
+    pub fn with_max(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn with_height(mut self, height: u16) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn data(&self) -> &[u64] {
+        &self.data
+    }
+
+    pub fn set_data(&mut self, data: Vec<u64>) {
+        self.data = data;
+    }
+
+    /// Appends the value, dropping the oldest values to keep at most `capacity` values.
+    pub fn push(&mut self, value: u64, capacity: usize) {
+        self.data.push(value);
+
+        if self.data.len() > capacity {
+            self.data.drain(..self.data.len() - capacity);
+        }
+    }
+}
+
+impl Styled for Sparkline {
+    type Item = Sparkline;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Sparkline {}
+
+impl Draw for Sparkline {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+        let mut grid = CellGrid::new(size, self.style);
+
+        let visible = &self.data[self.data.len().saturating_sub(size.width as usize)..];
+        let max = self
+            .max
+            .unwrap_or_else(|| visible.iter().copied().max().unwrap_or(0))
+            .max(1);
+        let total_eighths = u64::from(size.height) * 8;
+
+        for (x, value) in visible.iter().enumerate() {
+            let eighths = scaled_eighths(*value, max, total_eighths);
+
+            for row in 0..size.height {
+                let symbol = vertical_bar_symbol(eighths, u64::from(row));
+
+                grid.set(x as u16, size.height - row - 1, symbol, self.style);
+            }
+        }
+
+        grid.write_to(buffer).expect("Cannot write to buffer");
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        let width = self.data.len().min(u16::MAX as usize) as u16;
+
+        Size::new(width, self.height).clip(constraints)
+    }
+}
+
+impl Visual for Sparkline {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_eighths_does_not_overflow() {
+        assert_eq!(scaled_eighths(u64::MAX, u64::MAX, 80), 80);
+        assert_eq!(scaled_eighths(u64::MAX / 2, u64::MAX, 80), 39);
+        assert_eq!(scaled_eighths(1, u64::MAX, 80), 0);
+        assert_eq!(scaled_eighths(5, 4, 16), 16);
+    }
+}
@@ -0,0 +1,809 @@
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+};
+
+use crate::{
+    buffer::{write_cells, WriteBuffer},
+    input::{KeyCode, KeyEventArgs, MouseButtonEventArgs, MouseWheelEventArgs, VisualInput},
+    layout::Alignment,
+    style::{Attribute, Style, Styled},
+    visual::{Draw, Invalidation, MutableContext, Visual},
+    Position, Size,
+};
+
+/// Rule which defines width of the [`Table`] column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ColumnWidth {
+    /// Exact amount of cells.
+    Fixed(u16),
+    /// Percentage of the table width, without separators.
+    Percentage(u16),
+    /// Width of the widest cell of the column, including its header.
+    Auto,
+    /// Weighted share of the width left by other columns.
+    Fill(u16),
+}
+
+/// Order of the sorted [`Table`] column.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    fn reverse(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+
+    fn indicator(self) -> char {
+        match self {
+            SortOrder::Ascending => '▲',
+            SortOrder::Descending => '▼',
+        }
+    }
+}
+
+/// Describes a single column of the [`Table`].
+pub struct Column {
+    header: String,
+    width: ColumnWidth,
+    alignment: Alignment,
+}
+
+impl Column {
+    pub fn new(header: impl Into<String>, width: ColumnWidth) -> Self {
+        Self {
+            header: header.into(),
+            width,
+            alignment: Alignment::Left,
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+}
+
+/// Column widths and viewport computed during the last draw.
+///
+/// Input handlers need them to map positions to rows and columns.
+#[derive(Default)]
+struct TableArrangement {
+    widths: Vec<u16>,
+    viewport_width: u16,
+    viewport_height: u16,
+}
+
+/// Displays rows of cells under a header row.
+///
+/// Rows can be selected with arrow keys or mouse, and sorted by clicking column headers.
+/// When columns don't fit into the available width, the table can be scrolled horizontally
+/// with Left and Right keys or horizontal mouse wheel.
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+
+    style: Style,
+    header_style: Style,
+    alternate_row_style: Option<Style>,
+    selected_style: Style,
+    separator: Option<char>,
+
+    selected: Option<usize>,
+    sorting: Option<(usize, SortOrder)>,
+
+    /// Index of the first visible row.
+    row_offset: Cell<usize>,
+    /// Amount of cells scrolled horizontally.
+    column_offset: Cell<u16>,
+    arrangement: RefCell<TableArrangement>,
+    /// Width of the widest cell of each column, computed when the rows change.
+    content_widths: RefCell<Option<Vec<u16>>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+            style: Style::default(),
+            header_style: Style::default().attributes(Attribute::Bold.into()),
+            alternate_row_style: None,
+            selected_style: Style::default().attributes(Attribute::Reverse.into()),
+            separator: Some('│'),
+            selected: None,
+            sorting: None,
+            row_offset: Cell::new(0),
+            column_offset: Cell::new(0),
+            arrangement: RefCell::new(TableArrangement::default()),
+            content_widths: RefCell::new(None),
+        }
+    }
+
+    pub fn with_rows(mut self, rows: Vec<Vec<String>>) -> Self {
+        self.set_rows(rows);
+        self
+    }
+
+    pub fn with_header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Sets style of every second row to get zebra striping.
+    pub fn with_alternate_row_style(mut self, style: Style) -> Self {
+        self.alternate_row_style = Some(style);
+        self
+    }
+
+    pub fn with_selected_style(mut self, style: Style) -> Self {
+        self.selected_style = style;
+        self
+    }
+
+    /// Sets symbol drawn between columns, `None` means no separator.
+    pub fn with_separator(mut self, separator: Option<char>) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// Replaces all rows, keeping the current sorting.
+    pub fn set_rows(&mut self, rows: Vec<Vec<String>>) {
+        self.rows = rows;
+        self.content_widths = RefCell::new(None);
+        self.selected = self.selected.filter(|&index| index < self.rows.len());
+
+        if let Some((column, order)) = self.sorting {
+            self.sort(column, order);
+        }
+    }
+
+    /// Appends the row, or inserts it at its place if the rows are sorted.
+    pub fn push_row(&mut self, row: Vec<String>) {
+        if let Some(widths) = self.content_widths.get_mut() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(text_width(cell));
+            }
+        }
+
+        let index = match self.sorting {
+            Some((column, order)) => self
+                .rows
+                .partition_point(|r| compare_rows(r, &row, column, order) != Ordering::Greater),
+            None => self.rows.len(),
+        };
+
+        self.rows.insert(index, row);
+
+        if let Some(selected) = self
+            .selected
+            .as_mut()
+            .filter(|selected| **selected >= index)
+        {
+            *selected += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_row(&self) -> Option<&[String]> {
+        self.selected.map(|index| self.rows[index].as_slice())
+    }
+
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|&index| index < self.rows.len());
+    }
+
+    pub fn sorting(&self) -> Option<(usize, SortOrder)> {
+        self.sorting
+    }
+
+    /// Sorts rows by the given column. Numbers come before other cells and are compared
+    /// as numbers, other cells are compared as strings.
+    ///
+    /// The selection follows the selected row.
+    pub fn sort(&mut self, column: usize, order: SortOrder) {
+        if column >= self.columns.len() {
+            return;
+        }
+
+        let selected = self.selected.map(|index| self.rows[index].clone());
+
+        self.rows.sort_by(|a, b| compare_rows(a, b, column, order));
+
+        self.selected = selected.and_then(|row| self.rows.iter().position(|r| *r == row));
+        self.sorting = Some((column, order));
+    }
+
+    /// Computes width of each column for the given available width.
+    fn column_widths(&self, available_width: u16) -> Vec<u16> {
+        let separators = self.separators_width();
+        let inner_width = available_width.saturating_sub(separators);
+
+        let mut widths: Vec<u16> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Percentage(percentage) => {
+                    (u32::from(inner_width) * u32::from(percentage.min(100)) / 100) as u16
+                }
+                ColumnWidth::Auto => self.content_width(index),
+                ColumnWidth::Fill(_) => 0,
+            })
+            .collect();
+
+        let used: u16 = widths
+            .iter()
+            .fold(0, |sum, width| sum.saturating_add(*width));
+        let total_weight: u32 = self
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Fill(weight) => u32::from(weight),
+                _ => 0,
+            })
+            .sum();
+
+        let mut remaining = inner_width.saturating_sub(used);
+        let free = u32::from(remaining);
+
+        for (index, column) in self.columns.iter().enumerate() {
+            if let ColumnWidth::Fill(weight) = column.width {
+                let share = (free * u32::from(weight))
+                    .checked_div(total_weight)
+                    .unwrap_or_default() as u16;
+
+                // columns which got no room still show their headers
+                let width = share.min(remaining).max(self.header_width(index));
+
+                remaining = remaining.saturating_sub(width);
+                widths[index] = width;
+            }
+        }
+
+        widths
+    }
+
+    fn separators_width(&self) -> u16 {
+        match self.separator {
+            Some(_) => self.columns.len().saturating_sub(1) as u16,
+            None => 0,
+        }
+    }
+
+    fn header_width(&self, column: usize) -> u16 {
+        // reserve room for the sort indicator
+        text_width(&self.columns[column].header).saturating_add(2)
+    }
+
+    fn content_width(&self, column: usize) -> u16 {
+        let mut content_widths = self.content_widths.borrow_mut();
+
+        let widths = content_widths.get_or_insert_with(|| {
+            (0..self.columns.len())
+                .map(|column| {
+                    self.rows
+                        .iter()
+                        .filter_map(|row| row.get(column))
+                        .map(|cell| text_width(cell))
+                        .fold(self.header_width(column), u16::max)
+                })
+                .collect()
+        });
+
+        widths[column]
+    }
+
+    fn total_width(widths: &[u16], separators: u16) -> u16 {
+        widths
+            .iter()
+            .fold(separators, |sum, width| sum.saturating_add(*width))
+    }
+
+    /// Composes a single line of the table, not clipped by the viewport.
+    fn compose_line<'a>(
+        &self,
+        widths: &[u16],
+        cells: impl Iterator<Item = (&'a str, Alignment)>,
+        style: Style,
+        cells_buffer: &mut Vec<(char, Style)>,
+    ) {
+        cells_buffer.clear();
+
+        for (index, ((text, alignment), width)) in cells.zip(widths).enumerate() {
+            if index > 0 {
+                if let Some(separator) = self.separator {
+                    cells_buffer.push((separator, style));
+                }
+            }
+
+            let width = *width as usize;
+            let text_width = text.chars().count().min(width);
+            let offset = alignment.offset(text_width as u16, width as u16) as usize;
+
+            cells_buffer.extend(std::iter::repeat_n((' ', style), offset));
+            cells_buffer.extend(text.chars().take(text_width).map(|c| (c, style)));
+            cells_buffer.extend(std::iter::repeat_n(
+                (' ', style),
+                width - offset - text_width,
+            ));
+        }
+    }
+
+    fn row_style(&self, index: usize) -> Style {
+        if self.selected == Some(index) {
+            self.selected_style
+        } else if index % 2 == 1 {
+            self.alternate_row_style.unwrap_or(self.style)
+        } else {
+            self.style
+        }
+    }
+
+    /// Scrolls rows so that the selected row is visible in the viewport of the given height.
+    fn scroll_to_selected(&self, visible_rows: usize) {
+        let mut offset = self.row_offset.get().min(self.rows.len().saturating_sub(1));
+
+        if let Some(selected) = self.selected {
+            if selected < offset {
+                offset = selected;
+            } else if visible_rows > 0 && selected >= offset + visible_rows {
+                offset = selected + 1 - visible_rows;
+            }
+        }
+
+        self.row_offset.set(offset);
+    }
+
+    fn move_selection(&mut self, delta: isize, visual_context: &mut dyn MutableContext) -> bool {
+        if self.rows.is_empty() {
+            return false;
+        }
+
+        let last = self.rows.len() - 1;
+        let selected = match self.selected {
+            Some(index) => index.saturating_add_signed(delta).min(last),
+            None if delta < 0 => last,
+            None => 0,
+        };
+
+        self.selected = Some(selected);
+        visual_context.invalidate(Invalidation::Render);
+
+        true
+    }
+
+    fn scroll_horizontally(
+        &mut self,
+        forward: bool,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let arrangement = self.arrangement.borrow();
+        let separator = u16::from(self.separator.is_some());
+        let total = Self::total_width(&arrangement.widths, self.separators_width());
+        let max_offset = total.saturating_sub(arrangement.viewport_width);
+        let offset = self.column_offset.get();
+
+        // scroll to the start of the neighbour column
+        let mut starts = vec![];
+        let mut x = 0u16;
+
+        for width in arrangement.widths.iter() {
+            starts.push(x.min(max_offset));
+            x = x.saturating_add(*width).saturating_add(separator);
+        }
+
+        starts.push(max_offset);
+
+        let new_offset = if forward {
+            starts.into_iter().find(|&start| start > offset)
+        } else {
+            starts.into_iter().rev().find(|&start| start < offset)
+        };
+
+        match new_offset {
+            Some(new_offset) => {
+                self.column_offset.set(new_offset);
+                visual_context.invalidate(Invalidation::Render);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns index of the column at the given x coordinate of the viewport.
+    fn column_at(&self, x: u16) -> Option<usize> {
+        let arrangement = self.arrangement.borrow();
+        let separator = u16::from(self.separator.is_some());
+        let x = x.saturating_add(self.column_offset.get());
+        let mut start = 0u16;
+
+        for (index, width) in arrangement.widths.iter().enumerate() {
+            if x < start.saturating_add(*width) {
+                return Some(index);
+            }
+
+            start = start.saturating_add(*width).saturating_add(separator);
+
+            if x < start {
+                // separator was hit
+                return None;
+            }
+        }
+
+        None
+    }
+}
+
+impl Styled for Table {
+    type Item = Table;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+impl VisualInput for Table {
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_key_press(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let page = self
+            .arrangement
+            .borrow()
+            .viewport_height
+            .saturating_sub(1)
+            .max(1) as isize;
+
+        match args.code {
+            KeyCode::Up => self.move_selection(-1, visual_context),
+            KeyCode::Down => self.move_selection(1, visual_context),
+            KeyCode::PageUp => self.move_selection(-page, visual_context),
+            KeyCode::PageDown => self.move_selection(page, visual_context),
+            KeyCode::Home => self.move_selection(isize::MIN, visual_context),
+            KeyCode::End => self.move_selection(isize::MAX, visual_context),
+            KeyCode::Left => self.scroll_horizontally(false, visual_context),
+            KeyCode::Right => self.scroll_horizontally(true, visual_context),
+            _ => false,
+        }
+    }
+
+    fn on_mouse_down(
+        &mut self,
+        args: &MouseButtonEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let position = args.position;
+
+        visual_context.set_focus(true);
+
+        if position.y == 0 {
+            let Some(column) = self.column_at(position.x) else {
+                return false;
+            };
+
+            let order = match self.sorting {
+                Some((sorted, order)) if sorted == column => order.reverse(),
+                _ => SortOrder::Ascending,
+            };
+
+            self.sort(column, order);
+        } else {
+            let index = self.row_offset.get() + (position.y - 1) as usize;
+
+            if index >= self.rows.len() {
+                return false;
+            }
+
+            self.selected = Some(index);
+        }
+
+        visual_context.invalidate(Invalidation::Render);
+
+        true
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        args: &MouseWheelEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if args.is_vertical {
+            self.move_selection(args.delta as isize, visual_context)
+        } else {
+            self.scroll_horizontally(args.delta > 0, visual_context)
+        }
+    }
+}
+
+impl Draw for Table {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.measure(available_size);
+        let widths = self.column_widths(size.width);
+        let total_width = Self::total_width(&widths, self.separators_width());
+        let visible_rows = size.height.saturating_sub(1) as usize;
+
+        let column_offset = self
+            .column_offset
+            .get()
+            .min(total_width.saturating_sub(size.width));
+
+        self.column_offset.set(column_offset);
+        self.scroll_to_selected(visible_rows);
+
+        let mut cells = vec![];
+
+        // header
+        if size.height > 0 {
+            let headers: Vec<String> = self
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| match self.sorting {
+                    Some((sorted, order)) if sorted == index => {
+                        format!("{} {}", column.header, order.indicator())
+                    }
+                    _ => column.header.clone(),
+                })
+                .collect();
+
+            self.compose_line(
+                &widths,
+                headers
+                    .iter()
+                    .zip(self.columns.iter())
+                    .map(|(header, column)| (header.as_str(), column.alignment)),
+                self.header_style,
+                &mut cells,
+            );
+
+            write_viewport(
+                buffer,
+                0,
+                &mut cells,
+                column_offset,
+                size.width,
+                self.header_style,
+            );
+        }
+
+        // rows
+        let first_row = self.row_offset.get();
+
+        for (y, index) in (first_row..self.rows.len()).take(visible_rows).enumerate() {
+            let row = &self.rows[index];
+
+            self.compose_line(
+                &widths,
+                self.columns
+                    .iter()
+                    .enumerate()
+                    .map(|(column_index, column)| {
+                        let text = row.get(column_index).map(String::as_str).unwrap_or("");
+
+                        (text, column.alignment)
+                    }),
+                self.row_style(index),
+                &mut cells,
+            );
+
+            let style = self.row_style(index);
+
+            write_viewport(
+                buffer,
+                y as u16 + 1,
+                &mut cells,
+                column_offset,
+                size.width,
+                style,
+            );
+        }
+
+        *self.arrangement.borrow_mut() = TableArrangement {
+            widths,
+            viewport_width: size.width,
+            viewport_height: size.height,
+        };
+
+        size
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        let height = (self.rows.len() + 1).min(u16::MAX as usize) as u16;
+
+        let fills_width = self.columns.iter().any(|column| {
+            matches!(
+                column.width,
+                ColumnWidth::Fill(_) | ColumnWidth::Percentage(_)
+            )
+        });
+
+        if fills_width {
+            return Size::new(constraints.width, height).clip(constraints);
+        }
+
+        let widths: Vec<u16> = (0..self.columns.len())
+            .map(|index| match self.columns[index].width {
+                ColumnWidth::Fixed(width) => width,
+                _ => self.content_width(index),
+            })
+            .collect();
+
+        let width = Self::total_width(&widths, self.separators_width());
+
+        Size::new(width, height).clip(constraints)
+    }
+}
+
+impl Visual for Table {
+    /// Sorting and selection are taken unless the new table has its own,
+    /// the selection follows the selected row.
+    fn reconcile(&mut self, previous: &dyn Visual) {
+        let Some(previous) = previous.downcast_ref::<Self>() else {
+            return;
+        };
+
+        if self.selected.is_none() {
+            self.selected = previous
+                .selected_row()
+                .and_then(|row| self.rows.iter().position(|r| r == row));
+        }
+
+        if let (None, Some((column, order))) = (self.sorting, previous.sorting) {
+            self.sort(column, order);
+        }
+
+        self.row_offset.set(previous.row_offset.get());
+        self.column_offset.set(previous.column_offset.get());
+    }
+}
+
+/// Writes the visible part of the composed line, padding it to the viewport width.
+fn write_viewport(
+    buffer: &mut dyn WriteBuffer,
+    y: u16,
+    cells: &mut Vec<(char, Style)>,
+    offset: u16,
+    width: u16,
+    style: Style,
+) {
+    let start = offset as usize;
+    let end = start + width as usize;
+
+    if cells.len() < end {
+        cells.resize(end, (' ', style));
+    }
+
+    write_cells(buffer, Position::new(0, y), &cells[start..end]).expect("Cannot write to buffer");
+}
+
+fn text_width(text: &str) -> u16 {
+    text.chars().count().min(u16::MAX as usize) as u16
+}
+
+fn compare_rows(a: &[String], b: &[String], column: usize, order: SortOrder) -> Ordering {
+    let a = a.get(column).map_or("", String::as_str);
+    let b = b.get(column).map_or("", String::as_str);
+    let ordering = compare_cells(a, b);
+
+    match order {
+        SortOrder::Ascending => ordering,
+        SortOrder::Descending => ordering.reverse(),
+    }
+}
+
+/// Compares cells by a total order, numbers come first, then strings.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{buffer::Terminal, visual::RetainedMutableContext};
+
+    fn table(cells: &[&str]) -> Table {
+        Table::new(vec![Column::new("Value", ColumnWidth::Fill(1))])
+            .with_rows(cells.iter().map(|cell| vec![cell.to_string()]).collect())
+    }
+
+    fn column(table: &Table) -> Vec<&str> {
+        table.rows().iter().map(|row| row[0].as_str()).collect()
+    }
+
+    #[test]
+    fn sort_orders_numbers_before_strings() {
+        let mut table = table(&["b", "10", "1a", "9", "NaN", "-1", "a"]);
+
+        table.sort(0, SortOrder::Ascending);
+
+        assert_eq!(column(&table), ["-1", "9", "10", "NaN", "1a", "a", "b"]);
+    }
+
+    #[test]
+    fn sort_mixed_cells_does_not_panic() {
+        let cells: Vec<String> = (0..200)
+            .map(|i| match i % 3 {
+                0 => format!("{i}"),
+                1 => format!("{i}a"),
+                _ => "NaN".to_string(),
+            })
+            .collect();
+        let cells: Vec<&str> = cells.iter().map(String::as_str).collect();
+        let mut table = table(&cells);
+
+        table.sort(0, SortOrder::Ascending);
+        table.sort(0, SortOrder::Descending);
+
+        assert_eq!(table.rows().len(), 200);
+    }
+
+    #[test]
+    fn push_row_keeps_sorting_and_selection() {
+        let mut table = table(&["1", "3", "5"]);
+
+        table.sort(0, SortOrder::Descending);
+        table.select(Some(1));
+        table.push_row(vec!["4".to_string()]);
+
+        assert_eq!(column(&table), ["5", "4", "3", "1"]);
+        assert_eq!(table.selected_row(), Some(&["3".to_string()][..]));
+    }
+
+    #[test]
+    fn wide_columns_do_not_overflow() {
+        let mut table = Table::new(vec![
+            Column::new("A", ColumnWidth::Fixed(u16::MAX / 2 + 1)),
+            Column::new("B", ColumnWidth::Fixed(u16::MAX / 2 + 1)),
+            Column::new("C", ColumnWidth::Fixed(1)),
+        ]);
+
+        table.draw(&mut Terminal::new(Vec::new()), Size::new(20, 5));
+
+        assert_eq!(table.column_at(u16::MAX - 1), Some(1));
+
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+
+        while table.scroll_horizontally(true, context) {}
+
+        assert_eq!(table.column_at(0), Some(1));
+    }
+}
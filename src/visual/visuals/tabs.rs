@@ -0,0 +1,1110 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+};
+
+use crate::{
+    buffer::{write_cells, VirtualBuffer, WriteBuffer},
+    input::{
+        ClickEventArgs, DragEventArgs, FocusDirection, KeyCode, KeyEventArgs, KeyModifiers,
+        MouseButtonEventArgs, MouseEventArgs, MouseWheelEventArgs, PasteEventArgs, VisualInput,
+    },
+    style::{Attribute, Style, Styled},
+    visual::{ChildMutableContext, Draw, Invalidation, MutableContext, Visual},
+    Position, Rect, Size, TimerId,
+};
+
+const SCROLL_LEFT_GLYPH: char = '◀';
+const SCROLL_RIGHT_GLYPH: char = '▶';
+
+/// Single page of the [`Tabs`].
+pub struct Tab {
+    title: String,
+    content: Box<dyn Visual>,
+    closable: bool,
+}
+
+impl Tab {
+    pub fn new(title: impl Into<String>, content: impl Visual + 'static) -> Self {
+        Self {
+            title: title.into(),
+            content: Box::new(content),
+            closable: false,
+        }
+    }
+
+    /// Makes the tab closable by clicking the close glyph after its title.
+    pub fn closable(mut self) -> Self {
+        self.closable = true;
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn content(&self) -> &dyn Visual {
+        &*self.content
+    }
+
+    pub fn content_mut(&mut self) -> &mut dyn Visual {
+        &mut *self.content
+    }
+}
+
+/// Part of the header strip which reacts to mouse clicks.
+#[derive(Clone, Copy)]
+enum HeaderHit {
+    Title(usize),
+    Close(usize),
+    ScrollLeft,
+    ScrollRight,
+}
+
+/// Horizontal region of the header strip computed during the last draw.
+struct HeaderRegion {
+    start: u16,
+    end: u16,
+    hit: HeaderHit,
+}
+
+/// Shows a header strip of tab titles and the content of the active tab below it.
+///
+/// Tabs are switched with Ctrl+Tab and Ctrl+Shift+Tab, number keys which are not handled
+/// by the active content, or by clicking a title. When titles don't fit into the header,
+/// the strip scrolls to the active tab and shows arrows which switch to neighbour tabs.
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+
+    style: Style,
+    active_style: Style,
+    close_glyph: char,
+
+    /// Is the tabs visual or its content focused.
+    is_focused: bool,
+    /// Is the content of the active tab focused.
+    is_content_focused: bool,
+    /// Is the content of the active tab under the pointer.
+    is_content_hovered: bool,
+    /// Has the content of the active tab captured the mouse.
+    is_content_captured: bool,
+    /// Has the content of the active tab received the last mouse press.
+    is_content_pressed: bool,
+
+    /// Index of the first tab visible in the header.
+    header_offset: Cell<usize>,
+    header_regions: RefCell<Vec<HeaderRegion>>,
+    /// Active tab and final size of its content after the last arrange,
+    /// `None` if the content has to be arranged again.
+    arranged_content: Cell<Option<(usize, Size)>>,
+    /// Size of the content returned by the last arrange.
+    content_size: Cell<Size>,
+    /// Whether the tabs visual was painted and only the content has to be painted again.
+    is_rendered: Cell<bool>,
+}
+
+impl Tabs {
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        Self {
+            tabs,
+            active: 0,
+            style: Style::default(),
+            active_style: Style::default().attributes(Attribute::Reverse.into()),
+            close_glyph: '×',
+            is_focused: false,
+            is_content_focused: false,
+            is_content_hovered: false,
+            is_content_captured: false,
+            is_content_pressed: false,
+            header_offset: Cell::new(0),
+            header_regions: RefCell::new(vec![]),
+            arranged_content: Cell::new(None),
+            content_size: Cell::new(Size::default()),
+            is_rendered: Cell::new(false),
+        }
+    }
+
+    pub fn with_active_style(mut self, style: Style) -> Self {
+        self.active_style = style;
+        self
+    }
+
+    pub fn with_close_glyph(mut self, glyph: char) -> Self {
+        self.close_glyph = glyph;
+        self
+    }
+
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    pub fn tab_mut(&mut self, index: usize) -> Option<&mut Tab> {
+        self.tabs.get_mut(index)
+    }
+
+    pub fn push(&mut self, tab: Tab) {
+        self.tabs.push(tab);
+    }
+
+    /// Removes the tab at the given index and returns it.
+    ///
+    /// If the content of the tab was focused, it loses focus and the focus moves to the content
+    /// of the new active tab or to the tabs visual itself. The mouse capture of the content
+    /// is released.
+    pub fn close(&mut self, index: usize, visual_context: &mut dyn MutableContext) -> Option<Tab> {
+        if index >= self.tabs.len() {
+            return None;
+        }
+
+        let was_content_focused = index == self.active && self.release_content(visual_context);
+        let tab = self.tabs.remove(index);
+
+        if self.active > index || self.active >= self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+
+        if was_content_focused {
+            self.is_content_focused = match self.active_tab_mut() {
+                Some(tab) => tab
+                    .content
+                    .focus_first(FocusDirection::Next, visual_context),
+                None => false,
+            };
+        }
+
+        visual_context.redraw();
+
+        Some(tab)
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn set_active(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active = index;
+        }
+    }
+
+    fn active_tab_mut(&mut self) -> Option<&mut Tab> {
+        self.tabs.get_mut(self.active)
+    }
+
+    /// Calls the function for the content of the active tab
+    /// and processes focus request of the content.
+    fn with_content<R: Default>(
+        &mut self,
+        visual_context: &mut dyn MutableContext,
+        f: impl FnOnce(&mut dyn Visual, &mut dyn MutableContext) -> R,
+    ) -> R {
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return R::default();
+        };
+
+        let mut context = ChildMutableContext::new(visual_context);
+        let result = f(&mut *tab.content, &mut context);
+        let mouse_capture_request = context.mouse_capture_request();
+
+        if let Some(invalidation) = context
+            .invalidation()
+            .filter(|invalidation| *invalidation > Invalidation::Descendants)
+        {
+            tab.content.invalidate(invalidation);
+        }
+
+        match context.focus_request() {
+            Some(true) => {
+                if !self.is_content_focused {
+                    self.is_content_focused = true;
+
+                    // otherwise the content is notified by the parent with the tabs visual
+                    if self.is_focused {
+                        tab.content.on_got_focus(visual_context);
+                    }
+                }
+
+                visual_context.set_focus(true);
+            }
+            Some(false) if self.is_content_focused => visual_context.set_focus(false),
+            _ => {}
+        }
+
+        match mouse_capture_request {
+            Some(true) => {
+                self.is_content_captured = true;
+                visual_context.set_mouse_capture(true);
+            }
+            Some(false) if self.is_content_captured => {
+                self.is_content_captured = false;
+                visual_context.set_mouse_capture(false);
+            }
+            _ => {}
+        }
+
+        result
+    }
+
+    /// Sends leave and enter events when the pointer moves between the header and the content.
+    fn update_hovered(&mut self, args: &MouseEventArgs, visual_context: &mut dyn MutableContext) {
+        let is_hovered = args.position.y > 0 || self.is_content_captured;
+
+        if is_hovered == self.is_content_hovered {
+            return;
+        }
+
+        self.is_content_hovered = is_hovered;
+
+        if is_hovered {
+            let args = args.relative_to(CONTENT_ORIGIN);
+
+            self.with_content(visual_context, |content, context| {
+                content.on_mouse_enter(&args, context)
+            });
+        } else {
+            self.with_content(visual_context, |content, context| {
+                content.on_mouse_leave(context)
+            });
+        }
+    }
+
+    /// Removes focus, hover and mouse capture from the content of the active tab
+    /// before it is hidden or closed. Returns whether the content was focused.
+    fn release_content(&mut self, visual_context: &mut dyn MutableContext) -> bool {
+        let was_focused = std::mem::take(&mut self.is_content_focused);
+        let was_hovered = std::mem::take(&mut self.is_content_hovered);
+        let was_captured = std::mem::take(&mut self.is_content_captured);
+
+        self.is_content_pressed = false;
+
+        if let Some(tab) = self.active_tab_mut() {
+            if was_focused {
+                tab.content.clear_focus(visual_context);
+            }
+
+            // the next content is entered by the following mouse move
+            if was_hovered {
+                tab.content.on_mouse_leave(visual_context);
+            }
+        }
+
+        if was_captured {
+            visual_context.set_mouse_capture(false);
+        }
+
+        was_focused
+    }
+
+    fn switch(&mut self, index: usize, visual_context: &mut dyn MutableContext) -> bool {
+        if index >= self.tabs.len() {
+            return false;
+        }
+
+        // focus moves from the previous content to the new one
+        self.release_content(visual_context);
+
+        self.active = index;
+        self.is_content_focused = self.is_focused
+            && self.tabs[index]
+                .content
+                .focus_first(FocusDirection::Next, visual_context);
+
+        visual_context.redraw();
+
+        true
+    }
+
+    fn switch_relative(&mut self, forward: bool, visual_context: &mut dyn MutableContext) -> bool {
+        let count = self.tabs.len();
+
+        if count == 0 {
+            return false;
+        }
+
+        let index = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+
+        self.switch(index, visual_context)
+    }
+
+    fn title_width(&self, tab: &Tab) -> u16 {
+        // " title " and optional "× "
+        let width = tab.title.chars().count() + 2 + if tab.closable { 2 } else { 0 };
+
+        width.min(u16::MAX as usize) as u16
+    }
+
+    fn header_width(&self) -> u16 {
+        self.tabs
+            .iter()
+            .fold(0, |sum: u16, tab| sum.saturating_add(self.title_width(tab)))
+    }
+
+    /// Adjusts the header offset so that the active tab is visible in the given width.
+    fn scroll_to_active(&self, width: u16) {
+        let mut offset = self.header_offset.get().min(self.active);
+
+        loop {
+            let arrows = if offset > 0 { 2 } else { 1 };
+            let titles: u16 = self.tabs[offset..=self.active]
+                .iter()
+                .fold(0, |sum: u16, tab| sum.saturating_add(self.title_width(tab)));
+
+            if offset == self.active || titles.saturating_add(arrows) <= width {
+                break;
+            }
+
+            offset += 1;
+        }
+
+        self.header_offset.set(offset);
+    }
+
+    fn draw_header(&self, buffer: &mut dyn WriteBuffer, width: u16) {
+        let mut regions = vec![];
+        let mut cells: Vec<(char, Style)> = vec![];
+
+        if self.header_width() > width && !self.tabs.is_empty() {
+            self.scroll_to_active(width);
+        } else {
+            self.header_offset.set(0);
+        }
+
+        let offset = self.header_offset.get();
+
+        if offset > 0 {
+            cells.push((SCROLL_LEFT_GLYPH, self.style));
+            regions.push(HeaderRegion {
+                start: 0,
+                end: 1,
+                hit: HeaderHit::ScrollLeft,
+            });
+        }
+
+        for (index, tab) in self.tabs.iter().enumerate().skip(offset) {
+            if cells.len() > width as usize {
+                break;
+            }
+
+            let style = if index == self.active {
+                self.active_style
+            } else {
+                self.style
+            };
+
+            let start = cells.len() as u16;
+            cells.push((' ', style));
+            cells.extend(tab.title.chars().map(|c| (c, style)));
+            cells.push((' ', style));
+
+            regions.push(HeaderRegion {
+                start,
+                end: cells.len() as u16,
+                hit: HeaderHit::Title(index),
+            });
+
+            if tab.closable {
+                regions.push(HeaderRegion {
+                    start: cells.len() as u16,
+                    end: cells.len() as u16 + 1,
+                    hit: HeaderHit::Close(index),
+                });
+
+                cells.push((self.close_glyph, style));
+                cells.push((' ', style));
+            }
+        }
+
+        if cells.len() > width as usize {
+            // titles are clipped, so leave the last cell for the right arrow
+            let limit = width.saturating_sub(1);
+
+            cells.truncate(limit as usize);
+            cells.push((SCROLL_RIGHT_GLYPH, self.style));
+
+            regions.retain_mut(|region| {
+                region.end = region.end.min(limit);
+                region.start < region.end
+            });
+            regions.push(HeaderRegion {
+                start: limit,
+                end: limit + 1,
+                hit: HeaderHit::ScrollRight,
+            });
+        }
+
+        cells.resize(width as usize, (' ', self.style));
+
+        write_cells(buffer, Position::new(0, 0), &cells).expect("Cannot write to buffer");
+
+        *self.header_regions.borrow_mut() = regions;
+    }
+
+    fn header_hit(&self, x: u16) -> Option<HeaderHit> {
+        self.header_regions
+            .borrow()
+            .iter()
+            .find(|region| x >= region.start && x < region.end)
+            .map(|region| region.hit)
+    }
+
+    fn on_header_click(&mut self, x: u16, visual_context: &mut dyn MutableContext) -> bool {
+        let Some(hit) = self.header_hit(x) else {
+            return false;
+        };
+
+        match hit {
+            HeaderHit::Title(index) => self.switch(index, visual_context),
+            HeaderHit::Close(index) => self.close(index, visual_context).is_some(),
+            HeaderHit::ScrollLeft => self.switch(self.active.saturating_sub(1), visual_context),
+            HeaderHit::ScrollRight => self.switch(self.active + 1, visual_context),
+        }
+    }
+}
+
+impl Styled for Tabs {
+    type Item = Tabs;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style(mut self, style: Style) -> Self::Item {
+        self.style = style;
+        self
+    }
+}
+
+/// Origin of the content in coordinates of the tabs visual.
+const CONTENT_ORIGIN: Position = Position::new(0, 1);
+
+impl VisualInput for Tabs {
+    fn on_paste(&mut self, args: &PasteEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        self.with_content(visual_context, |content, context| {
+            content.on_paste(args, context)
+        })
+    }
+
+    fn on_got_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        self.is_focused = true;
+
+        if self.is_content_focused {
+            if let Some(tab) = self.active_tab_mut() {
+                tab.content.on_got_focus(visual_context);
+            }
+        }
+    }
+
+    fn on_lost_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        if self.is_content_focused {
+            if let Some(tab) = self.active_tab_mut() {
+                tab.content.on_lost_focus(visual_context);
+            }
+        }
+    }
+
+    fn on_mouse_leave(&mut self, visual_context: &mut dyn MutableContext) {
+        if self.is_content_hovered {
+            self.is_content_hovered = false;
+            self.with_content(visual_context, |content, context| {
+                content.on_mouse_leave(context)
+            });
+        }
+    }
+
+    fn on_tick(&mut self, visual_context: &mut dyn MutableContext) {
+        self.with_content(visual_context, |content, context| content.on_tick(context))
+    }
+
+    /// The new size is delivered to contents of all tabs, so inactive tabs are up to date
+    /// when they are activated.
+    fn on_resize(&mut self, size: Size, visual_context: &mut dyn MutableContext) {
+        self.with_content(visual_context, |content, context| {
+            content.on_resize(size, context)
+        });
+
+        let active = self.active;
+
+        for (_, tab) in self
+            .tabs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+        {
+            tab.content
+                .on_resize(size, &mut ChildMutableContext::new(visual_context));
+        }
+    }
+
+    /// Timers are delivered to contents of all tabs, so inactive tabs keep their timers.
+    fn on_timer(&mut self, timer: TimerId, visual_context: &mut dyn MutableContext) -> bool {
+        if self.with_content(visual_context, |content, context| {
+            content.on_timer(timer, context)
+        }) {
+            return true;
+        }
+
+        let active = self.active;
+
+        self.tabs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .any(|(_, tab)| {
+                // focus and mouse capture requests of inactive contents are ignored
+                tab.content
+                    .on_timer(timer, &mut ChildMutableContext::new(visual_context))
+            })
+    }
+
+    /// Messages are delivered to contents of all tabs like timers.
+    fn on_message(&mut self, message: &dyn Any, visual_context: &mut dyn MutableContext) -> bool {
+        if self.with_content(visual_context, |content, context| {
+            content.on_message(message, context)
+        }) {
+            return true;
+        }
+
+        let active = self.active;
+
+        self.tabs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .any(|(_, tab)| {
+                tab.content
+                    .on_message(message, &mut ChildMutableContext::new(visual_context))
+            })
+    }
+
+    /// The tabs visual is focusable itself to switch tabs by keys when its content has
+    /// no focusable visuals. Otherwise, focus moves to the content of the active tab.
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn focus_first(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.is_focused = true;
+        self.is_content_focused = match self.active_tab_mut() {
+            Some(tab) => tab.content.focus_first(direction, visual_context),
+            None => false,
+        };
+
+        true
+    }
+
+    fn focus_next(
+        &mut self,
+        direction: FocusDirection,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let is_content_focused = self.is_content_focused;
+
+        // the tabs visual precedes its content in the focus order
+        let moved = match self.active_tab_mut() {
+            Some(tab) if is_content_focused => tab.content.focus_next(direction, visual_context),
+            Some(tab) if direction == FocusDirection::Next => {
+                tab.content.focus_first(direction, visual_context)
+            }
+            _ => false,
+        };
+
+        // moving back from the content focuses the tabs visual itself
+        let is_focused = moved || (is_content_focused && direction == FocusDirection::Previous);
+
+        self.is_content_focused = moved;
+        self.is_focused = is_focused;
+
+        is_focused
+    }
+
+    fn clear_focus(&mut self, visual_context: &mut dyn MutableContext) {
+        if self.is_content_focused {
+            if let Some(tab) = self.active_tab_mut() {
+                tab.content.clear_focus(visual_context);
+            }
+        }
+
+        self.is_content_focused = false;
+        self.is_focused = false;
+    }
+
+    fn on_key_press(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        // tab switching keys are handled before the content
+        if args.modifiers.contains(KeyModifiers::CONTROL) {
+            match args.code {
+                KeyCode::Tab if args.modifiers.contains(KeyModifiers::SHIFT) => {
+                    return self.switch_relative(false, visual_context)
+                }
+                KeyCode::Tab => return self.switch_relative(true, visual_context),
+                KeyCode::BackTab => return self.switch_relative(false, visual_context),
+                _ => {}
+            }
+        }
+
+        let handled = self.with_content(visual_context, |content, context| {
+            content.on_key_press(args, context)
+        });
+
+        if handled {
+            return true;
+        }
+
+        match args.code {
+            KeyCode::Char(digit @ '1'..='9') => {
+                let index = digit as usize - '1' as usize;
+
+                self.switch(index, visual_context)
+            }
+            _ => false,
+        }
+    }
+
+    fn on_key_release(
+        &mut self,
+        args: &KeyEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.with_content(visual_context, |content, context| {
+            content.on_key_release(args, context)
+        })
+    }
+
+    fn on_mouse_move(
+        &mut self,
+        args: &MouseEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.update_hovered(args, visual_context);
+
+        if args.position.y == 0 && !self.is_content_captured {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_mouse_move(&args, context)
+        })
+    }
+
+    fn on_mouse_wheel(
+        &mut self,
+        args: &MouseWheelEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if args.position.y == 0 && !self.is_content_captured {
+            return self.switch_relative(args.delta > 0, visual_context);
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_mouse_wheel(&args, context)
+        })
+    }
+
+    fn on_mouse_up(
+        &mut self,
+        args: &MouseButtonEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if args.position.y == 0 && !self.is_content_captured {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_mouse_up(&args, context)
+        })
+    }
+
+    fn on_mouse_down(
+        &mut self,
+        args: &MouseButtonEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        self.is_content_pressed = args.position.y > 0 || self.is_content_captured;
+
+        if !self.is_content_pressed {
+            visual_context.set_focus(true);
+
+            return self.on_header_click(args.position.x, visual_context);
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_mouse_down(&args, context)
+        })
+    }
+
+    fn on_click(&mut self, args: &ClickEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        let is_content_pressed = std::mem::take(&mut self.is_content_pressed);
+
+        if !is_content_pressed || (args.position.y == 0 && !self.is_content_captured) {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_click(&args, context)
+        })
+    }
+
+    fn on_drag_start(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        if !self.is_content_pressed && !self.is_content_captured {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_drag_start(&args, context)
+        })
+    }
+
+    fn on_drag(&mut self, args: &DragEventArgs, visual_context: &mut dyn MutableContext) -> bool {
+        if !self.is_content_pressed && !self.is_content_captured {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_drag(&args, context)
+        })
+    }
+
+    fn on_drag_end(
+        &mut self,
+        args: &DragEventArgs,
+        visual_context: &mut dyn MutableContext,
+    ) -> bool {
+        let is_content_pressed = std::mem::take(&mut self.is_content_pressed);
+
+        if !is_content_pressed && !self.is_content_captured {
+            return false;
+        }
+
+        let args = args.relative_to(CONTENT_ORIGIN);
+
+        self.with_content(visual_context, |content, context| {
+            content.on_drag_end(&args, context)
+        })
+    }
+}
+
+impl Draw for Tabs {
+    fn draw(&self, buffer: &mut dyn WriteBuffer, available_size: Size) -> Size {
+        let size = self.arrange(available_size);
+
+        self.render(buffer, size);
+
+        size
+    }
+
+    fn arrange(&self, final_size: Size) -> Size {
+        if final_size.height == 0 || final_size.width == 0 {
+            return Size::default();
+        }
+
+        let content_size = Size::new(final_size.width, final_size.height - 1);
+        let arranged_content = Some((self.active, content_size));
+
+        let content_size = match self.tabs.get(self.active) {
+            Some(tab) if content_size.height > 0 => {
+                // invalidations of inactive contents are not saved,
+                // so the content is computed again when it becomes active
+                if self.arranged_content.replace(arranged_content) != arranged_content {
+                    tab.content.invalidate(Invalidation::Measure);
+                    tab.content.invalidate(Invalidation::Render);
+                }
+
+                tab.content.arrange(content_size)
+            }
+            _ => Size::default(),
+        };
+
+        self.content_size.set(content_size);
+
+        Size::new(final_size.width, content_size.height + 1)
+    }
+
+    fn render(&self, buffer: &mut dyn WriteBuffer, size: Size) {
+        if size.height == 0 || size.width == 0 {
+            return;
+        }
+
+        self.draw_header(buffer, size.width);
+
+        let content_size = self.content_size.get();
+
+        if let Some(tab) = self
+            .tabs
+            .get(self.active)
+            .filter(|_| content_size.height > 0)
+        {
+            if !self.is_rendered.replace(true) {
+                tab.content.invalidate(Invalidation::Render);
+            }
+
+            let mut virtual_buffer =
+                VirtualBuffer::new(buffer, Rect::from((CONTENT_ORIGIN, content_size)));
+
+            tab.content.render(&mut virtual_buffer, content_size);
+        }
+    }
+
+    fn measure(&self, constraints: Size) -> Size {
+        if constraints.height == 0 {
+            return Size::default();
+        }
+
+        let content_constraints = Size::new(constraints.width, constraints.height - 1);
+        let content_size = match self.tabs.get(self.active) {
+            Some(tab) => tab.content.measure(content_constraints),
+            None => Size::default(),
+        };
+
+        Size::new(
+            self.header_width().max(content_size.width),
+            content_size.height + 1,
+        )
+        .clip(constraints)
+    }
+
+    /// Only the content of the active tab is drawn, so animations of others are ignored.
+    fn is_animating(&self) -> bool {
+        self.tabs
+            .get(self.active)
+            .is_some_and(|tab| tab.content.is_animating())
+    }
+
+    fn invalidate(&self, invalidation: Invalidation) {
+        match invalidation {
+            Invalidation::Descendants => {}
+            Invalidation::Render => self.is_rendered.set(false),
+            Invalidation::Arrange | Invalidation::Measure => self.arranged_content.set(None),
+        }
+    }
+}
+
+impl Visual for Tabs {
+    fn visit_children<'a>(&'a self, visitor: &mut dyn FnMut(&'a dyn Visual)) {
+        for tab in &self.tabs {
+            visitor(&*tab.content);
+        }
+    }
+
+    fn visit_children_mut<'a>(&'a mut self, visitor: &mut dyn FnMut(&'a mut dyn Visual)) {
+        for tab in &mut self.tabs {
+            visitor(tab.content.as_mut());
+        }
+    }
+
+    /// Tabs are matched by their titles, the active tab stays active if it still exists.
+    fn reconcile(&mut self, previous: &dyn Visual) {
+        let Some(previous) = previous.downcast_ref::<Self>() else {
+            return;
+        };
+
+        for tab in &mut self.tabs {
+            if let Some(previous_tab) = previous.tabs.iter().find(|t| t.title == tab.title) {
+                tab.content.reconcile(&*previous_tab.content);
+            }
+        }
+
+        self.is_focused = previous.is_focused;
+        self.header_offset.set(previous.header_offset.get());
+
+        let active = previous
+            .tabs
+            .get(previous.active)
+            .and_then(|active| self.tabs.iter().position(|t| t.title == active.title));
+
+        if let Some(active) = active {
+            self.active = active;
+            self.is_content_focused = previous.is_content_focused;
+            self.is_content_hovered = previous.is_content_hovered;
+            self.is_content_captured = previous.is_content_captured;
+            self.is_content_pressed = previous.is_content_pressed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        buffer::Terminal,
+        input::{KeyEventState, MouseButton},
+        visual::{MutableContextAction, RetainedMutableContext, TextBlock},
+    };
+
+    /// Focusable content which captures the mouse when pressed.
+    #[derive(Default)]
+    struct Field {
+        is_focused: bool,
+        is_hovered: bool,
+    }
+
+    impl VisualInput for Field {
+        fn is_focusable(&self) -> bool {
+            true
+        }
+
+        fn on_got_focus(&mut self, _: &mut dyn MutableContext) {
+            self.is_focused = true;
+        }
+
+        fn on_lost_focus(&mut self, _: &mut dyn MutableContext) {
+            self.is_focused = false;
+        }
+
+        fn on_mouse_enter(&mut self, _: &MouseEventArgs, _: &mut dyn MutableContext) {
+            self.is_hovered = true;
+        }
+
+        fn on_mouse_leave(&mut self, _: &mut dyn MutableContext) {
+            self.is_hovered = false;
+        }
+
+        fn on_mouse_down(
+            &mut self,
+            _: &MouseButtonEventArgs,
+            visual_context: &mut dyn MutableContext,
+        ) -> bool {
+            visual_context.set_mouse_capture(true);
+            true
+        }
+    }
+
+    impl Draw for Field {
+        fn draw(&self, _: &mut dyn WriteBuffer, _: Size) -> Size {
+            Size::new(1, 1)
+        }
+
+        fn measure(&self, _: Size) -> Size {
+            Size::new(1, 1)
+        }
+    }
+
+    impl Visual for Field {}
+
+    fn field(tab: &Tab) -> &Field {
+        tab.content().downcast_ref().unwrap()
+    }
+
+    fn field_tabs(count: usize) -> Tabs {
+        Tabs::new(
+            (0..count)
+                .map(|index| Tab::new(index.to_string(), Field::default()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn switch_moves_focus_to_new_content() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tabs = field_tabs(2);
+        let args = KeyEventArgs::new(KeyCode::Tab, KeyModifiers::CONTROL, KeyEventState::NONE);
+
+        tabs.focus_first(FocusDirection::Next, context);
+        tabs.on_key_press(&args, context);
+
+        assert_eq!(tabs.active(), 1);
+        assert!(!field(&tabs.tabs()[0]).is_focused);
+        assert!(field(&tabs.tabs()[1]).is_focused);
+    }
+
+    #[test]
+    fn close_keeps_active_tab() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tabs = field_tabs(3);
+
+        tabs.set_active(2);
+        tabs.close(0, context);
+
+        assert_eq!(tabs.active(), 1);
+        assert_eq!(tabs.tabs()[tabs.active()].title(), "2");
+    }
+
+    #[test]
+    fn close_moves_focus_from_closed_content_to_next_one() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tabs = field_tabs(2);
+
+        tabs.focus_first(FocusDirection::Next, context);
+
+        let closed = tabs.close(0, context).unwrap();
+
+        assert!(!field(&closed).is_focused);
+        assert!(tabs.is_content_focused);
+        assert!(field(&tabs.tabs()[0]).is_focused);
+    }
+
+    #[test]
+    fn close_moves_focus_from_closed_content_to_header() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tabs = Tabs::new(vec![
+            Tab::new("a", TextBlock::new("a".to_owned())),
+            Tab::new("b", Field::default()),
+        ]);
+
+        tabs.set_active(1);
+        tabs.focus_first(FocusDirection::Next, context);
+
+        let closed = tabs.close(1, context).unwrap();
+
+        assert!(!field(&closed).is_focused);
+        assert!(tabs.is_focused);
+        assert!(!tabs.is_content_focused);
+        assert!(!actions
+            .iter()
+            .any(|action| matches!(action, MutableContextAction::SetFocus(false))));
+    }
+
+    #[test]
+    fn close_releases_mouse_capture_of_content() {
+        let mut actions = vec![];
+        let context = &mut RetainedMutableContext::new(&mut actions);
+        let mut tabs = field_tabs(2);
+        let position = Position::new(0, 1);
+        let args = MouseButtonEventArgs::new(position, KeyModifiers::NONE, MouseButton::Left);
+
+        tabs.draw(&mut Terminal::new(Vec::new()), Size::new(10, 2));
+        tabs.on_mouse_move(&MouseEventArgs::new(position, KeyModifiers::NONE), context);
+        tabs.on_mouse_down(&args, context);
+
+        let closed = tabs.close(0, context).unwrap();
+
+        assert!(!field(&closed).is_hovered);
+        assert!(!tabs.is_content_captured);
+        assert!(actions
+            .iter()
+            .any(|action| matches!(action, MutableContextAction::SetMouseCapture(false))));
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::{
     buffer::WriteBuffer,
     input::VisualInput,
@@ -6,13 +8,20 @@ use crate::{
     Position, Size,
 };
 
+/// Single line of text.
+///
+/// Only text blocks with `'static` or owned text are [`Visual`]s, since visuals are
+/// downcast by lookups in the visual tree, borrowed text is passed as an owned string,
+/// e.g. `TextBlock::new(text.to_owned())`.
 pub struct TextBlock<'a> {
-    text: &'a str,
+    text: Cow<'a, str>,
     style: Style,
 }
 
 impl<'a> TextBlock<'a> {
-    pub fn new(text: &'a str) -> Self {
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
+        let text = text.into();
+
         if text.len() > u16::MAX as usize {
             panic!("text length should be less than u16.MAX");
         }
@@ -22,6 +31,22 @@ impl<'a> TextBlock<'a> {
             style: Style::default(),
         }
     }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, it is painted by the next draw if the visual is invalidated,
+    /// which lookups for mutation do, e.g. `find_mut`.
+    pub fn set_text(&mut self, text: impl Into<Cow<'a, str>>) {
+        let text = text.into();
+
+        if text.len() > u16::MAX as usize {
+            panic!("text length should be less than u16.MAX");
+        }
+
+        self.text = text;
+    }
 }
 
 impl<'a> Styled for TextBlock<'a> {
@@ -44,7 +69,7 @@ impl<'a> Draw for TextBlock<'a> {
         let width = (self.text.len() as u16).min(available_size.width);
 
         buffer
-            .write_symbols(Position::default(), self.text, self.style)
+            .write_symbols(Position::default(), &self.text, self.style)
             .expect("Cannot write to buffer");
 
         Size::new(width, 1)
@@ -57,4 +82,4 @@ impl<'a> Draw for TextBlock<'a> {
     }
 }
 
-impl<'a> Visual for TextBlock<'a> {}
+impl Visual for TextBlock<'static> {}